@@ -8,14 +8,16 @@
 )]
 
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
+	ops::Range,
 	sync::{Arc, RwLock, Weak},
 };
 
 use async_trait::async_trait;
 use atspi_client::{convertable::Convertable, text_ext::TextExt};
 use atspi_common::{
-	ClipType, CoordType, GenericEvent, Granularity, InterfaceSet, RelationType, Role, StateSet,
+	ClipType, CoordType, GenericEvent, Granularity, Interface, InterfaceSet, RelationType,
+	Role, StateSet,
 };
 use atspi_proxies::{
 	accessible::{Accessible, AccessibleProxy},
@@ -37,6 +39,8 @@ use zbus::{
 type CacheKey = AccessiblePrimitive;
 type InnerCache = DashMap<CacheKey, Arc<RwLock<CacheItem>>, FxBuildHasher>;
 type ThreadSafeCache = Arc<InnerCache>;
+type TableCache = Arc<DashMap<CacheKey, TableInfo, FxBuildHasher>>;
+type HyperlinkCache = Arc<DashMap<CacheKey, Vec<(i32, CacheKey)>, FxBuildHasher>>;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 /// A struct which represents the bare minimum of an accessible for purposes of caching.
@@ -179,6 +183,10 @@ pub struct CacheItem {
 	pub text: String,
 	// The children (ids) of the accessible.
 	pub children: Vec<CacheRef>,
+	/// The runs of text attributes fetched so far, in no particular order; see [`Cache::get_or_create_attribute_run`].
+	/// These are cleared whenever the text or its attributes change.
+	#[serde(skip)]
+	pub attribute_runs: Vec<AttributeRun>,
 
 	#[serde(skip)]
 	pub cache: Weak<Cache>,
@@ -251,6 +259,7 @@ impl CacheItem {
 			text: atspi_cache_item.name,
 			cache,
 			children,
+			attribute_runs: Vec::new(),
 		})
 	}
 	// Same as [`Accessible::get_children`], just offered as a non-async version.
@@ -656,6 +665,46 @@ impl Text for CacheItem {
 	}
 }
 
+/// The structure of a table, as reported by the `Table` interface of the table itself.
+/// This is stored in [`Cache::tables`] rather than being fetched every time the user moves between cells.
+/// Headers take a `DBus` call each, so they are only fetched once they are needed; see [`Cache::get_or_create_table_header`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TableInfo {
+	pub rows: i32,
+	pub columns: i32,
+	/// The names of the headers of the columns looked up so far, by column; an empty string means the column has no header.
+	pub column_headers: HashMap<i32, String>,
+	/// The names of the headers of the rows looked up so far, by row; an empty string means the row has no header.
+	pub row_headers: HashMap<i32, String>,
+}
+
+/// Which of the headers of a cell to look up: the one for its row, or the one for its column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableHeader {
+	Row,
+	Column,
+}
+
+/// A run of text with the same attributes (font, weight, colour, spelling errors and so on), as reported by the `Text` interface.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AttributeRun {
+	/// The character offset of the start of the run.
+	pub start: i32,
+	/// The character offset just after the end of the run.
+	pub end: i32,
+	/// The attributes of the text in the run, including those which are the defaults for the whole text.
+	pub attributes: HashMap<String, String>,
+}
+
+impl AttributeRun {
+	/// Whether the run covers the character offset `offset`; an empty run covers the offset it is at, which happens at the end of the text.
+	#[must_use]
+	pub fn contains(&self, offset: i32) -> bool {
+		(self.start..self.end).contains(&offset)
+			|| (self.start == self.end && self.start == offset)
+	}
+}
+
 /// An internal cache used within Odilia.
 ///
 /// This contains (mostly) all accessibles in the entire accessibility tree, and
@@ -664,6 +713,10 @@ impl Text for CacheItem {
 #[derive(Clone, Debug)]
 pub struct Cache {
 	pub by_id: ThreadSafeCache,
+	/// Table structures, keyed by the table they describe. See [`TableInfo`].
+	pub tables: TableCache,
+	/// The embedded objects within the text of an item, keyed by that item, as pairs of the character offset of the object replacement character (U+FFFC) standing in for each object, and the object itself.
+	pub hyperlinks: HyperlinkCache,
 	pub connection: zbus::Connection,
 }
 
@@ -679,6 +732,8 @@ impl Cache {
 				10_000,
 				FxBuildHasher::default(),
 			)),
+			tables: Arc::new(DashMap::with_hasher(FxBuildHasher::default())),
+			hyperlinks: Arc::new(DashMap::with_hasher(FxBuildHasher::default())),
 			connection: conn,
 		}
 	}
@@ -732,6 +787,52 @@ impl Cache {
 		ids.iter().map(|id| self.get(id)).collect()
 	}
 
+	/// Get every ancestor of an item, starting with its parent and ending with the top-most ancestor found in the cache.
+	/// The walk stops early if a parent is missing from the cache, or if an item is its own ancestor.
+	#[must_use]
+	pub fn get_ancestors(&self, id: &CacheKey) -> Vec<CacheItem> {
+		let mut ancestors = Vec::new();
+		let mut visited = HashSet::from([id.clone()]);
+		let mut parent_key = match self.get(id) {
+			Some(item) => item.parent.key,
+			None => return ancestors,
+		};
+		while visited.insert(parent_key.clone()) {
+			let Some(parent) = self.get(&parent_key) else {
+				break;
+			};
+			parent_key = parent.parent.key.clone();
+			ancestors.push(parent);
+		}
+		ancestors
+	}
+
+	/// Get every descendant of an item in document order (a pre-order, depth-first walk of the tree), not including the item itself.
+	/// Children which can not be found in the cache are skipped, along with their own descendants.
+	#[must_use]
+	pub fn get_descendants(&self, id: &CacheKey) -> Vec<CacheItem> {
+		let mut descendants = Vec::new();
+		// guards against applications which (incorrectly) report cycles in their tree
+		let mut visited = HashSet::from([id.clone()]);
+		let mut stack = match self.get(id) {
+			Some(root) => root.children.into_iter().rev().collect::<Vec<_>>(),
+			None => return descendants,
+		};
+		while let Some(child_ref) = stack.pop() {
+			if !visited.insert(child_ref.key.clone()) {
+				continue;
+			}
+			let Some(child) =
+				child_ref.clone_inner().or_else(|| self.get(&child_ref.key))
+			else {
+				continue;
+			};
+			stack.extend(child.children.iter().rev().cloned());
+			descendants.push(child);
+		}
+		descendants
+	}
+
 	/// Bulk add many items to the cache; only one accessible should ever be
 	/// associated with an id.
 	/// # Errors
@@ -812,6 +913,187 @@ impl Cache {
 		Ok(cache_item)
 	}
 
+	/// Get the structure of a table from the cache.
+	/// If it has not been cached yet, ask the table for its size, then cache the result.
+	/// # Errors
+	/// The function will return an error if the `table` does not implement the `Table` interface, or if any of the calls to query its size fail.
+	pub async fn get_or_create_table(&self, table: &CacheKey) -> OdiliaResult<TableInfo> {
+		if let Some(info) = self.tables.get(table) {
+			return Ok(info.clone());
+		}
+		let table_proxy = table
+			.clone()
+			.into_accessible(&self.connection)
+			.await?
+			.to_table()
+			.await?;
+		let (rows, columns) =
+			tokio::try_join!(table_proxy.nrows(), table_proxy.ncolumns())?;
+		let info = TableInfo { rows, columns, ..TableInfo::default() };
+		self.tables.insert(table.clone(), info.clone());
+		Ok(info)
+	}
+
+	/// Get the name of the header of row or column `index` of a table from the cache.
+	/// If it has not been looked up yet, ask the table for it, then cache the result.
+	/// # Errors
+	/// The function will return an error if the `table` does not implement the `Table` interface, or if its size can not be queried.
+	/// A header which can not be queried is treated as empty instead.
+	pub async fn get_or_create_table_header(
+		&self,
+		table: &CacheKey,
+		header: TableHeader,
+		index: i32,
+	) -> OdiliaResult<String> {
+		let info = self.get_or_create_table(table).await?;
+		let known = match header {
+			TableHeader::Row => info.row_headers.get(&index),
+			TableHeader::Column => info.column_headers.get(&index),
+		};
+		if let Some(name) = known {
+			return Ok(name.clone());
+		}
+		let table_proxy = table
+			.clone()
+			.into_accessible(&self.connection)
+			.await?
+			.to_table()
+			.await?;
+		let reply = match header {
+			TableHeader::Row => table_proxy.get_row_header(index).await,
+			TableHeader::Column => table_proxy.get_column_header(index).await,
+		};
+		let name = self.header_name(reply).await;
+		if let Some(mut info) = self.tables.get_mut(table) {
+			let headers = match header {
+				TableHeader::Row => &mut info.row_headers,
+				TableHeader::Column => &mut info.column_headers,
+			};
+			headers.insert(index, name.clone());
+		}
+		Ok(name)
+	}
+
+	async fn header_name(&self, header: zbus::Result<(String, OwnedObjectPath)>) -> String {
+		let Ok(header) = header else {
+			return String::new();
+		};
+		if header.1.as_str() == "/org/a11y/atspi/null" {
+			return String::new();
+		}
+		let header_prim = AccessiblePrimitive::from(header);
+		if let Some(item) = self.get(&header_prim) {
+			if !item.text.is_empty() {
+				return item.text;
+			}
+		}
+		match header_prim.into_accessible(&self.connection).await {
+			Ok(accessible) => accessible.name().await.unwrap_or_default(),
+			Err(_) => String::new(),
+		}
+	}
+
+	/// Forget the structure of a table, so that it is fetched again next time it is needed.
+	/// This should be called whenever rows or columns are added, removed or reordered.
+	pub fn remove_table(&self, table: &CacheKey) {
+		self.tables.remove(table);
+	}
+
+	/// Get the embedded objects within the text of `item` from the cache, as pairs of the character offset of each object and the object itself.
+	/// If they have not been cached yet, ask the `Hypertext` interface of the item for its links, then cache the result.
+	/// Items without the `Hypertext` interface have no embedded objects.
+	/// # Errors
+	/// The function will return an error if any of the calls to query the links of the item fail.
+	pub async fn get_or_create_hyperlinks(
+		&self,
+		item: &CacheKey,
+	) -> OdiliaResult<Vec<(i32, CacheKey)>> {
+		if let Some(links) = self.hyperlinks.get(item) {
+			return Ok(links.clone());
+		}
+		let accessible = item.clone().into_accessible(&self.connection).await?;
+		let mut links = Vec::new();
+		if accessible.get_interfaces().await?.contains(Interface::Hypertext) {
+			let hypertext = accessible.to_hypertext().await?;
+			for index in 0..hypertext.get_nlinks().await? {
+				let link =
+					AccessiblePrimitive::from(hypertext.get_link(index).await?)
+						.into_accessible(&self.connection)
+						.await?
+						.to_hyperlink()
+						.await?;
+				let (offset, object) =
+					tokio::try_join!(link.start_index(), link.get_object(0))?;
+				links.push((offset, AccessiblePrimitive::from(object)));
+			}
+		}
+		self.hyperlinks.insert(item.clone(), links.clone());
+		Ok(links)
+	}
+
+	/// Get the run of text attributes of `item` which covers the character offset `offset` from the cache.
+	/// If it has not been cached yet, ask the `Text` interface of the item for it, then cache the result within the item.
+	/// # Errors
+	/// The function will return an error if `item` is not in the cache, does not implement the `Text` interface, or if the call to query the run fails.
+	pub async fn get_or_create_attribute_run(
+		&self,
+		item: &CacheKey,
+		offset: i32,
+	) -> OdiliaResult<AttributeRun> {
+		let cache_item = self.get(item).ok_or(CacheError::NoItem)?;
+		if let Some(run) = cache_item.attribute_runs.iter().find(|run| run.contains(offset))
+		{
+			return Ok(run.clone());
+		}
+		let (attributes, start, end) = cache_item.get_attribute_run(offset, true).await?;
+		let run = AttributeRun { start, end, attributes };
+		let cached = run.clone();
+		self.modify_item(item, move |cache_item| cache_item.attribute_runs.push(cached))?;
+		Ok(run)
+	}
+
+	/// Get the runs of text attributes overlapping the character offsets `range` of the text of `item`, in order, fetching whichever are not cached yet.
+	/// Only the part of the range within the text is covered.
+	/// # Errors
+	/// The function will return an error if any run can not be fetched; see [`Self::get_or_create_attribute_run`].
+	pub async fn get_or_create_attribute_runs(
+		&self,
+		item: &CacheKey,
+		range: Range<i32>,
+	) -> OdiliaResult<Vec<AttributeRun>> {
+		let length = i32::try_from(
+			self.get(item).ok_or(CacheError::NoItem)?.text.chars().count(),
+		)?;
+		let end = range.end.min(length);
+		let mut runs = Vec::new();
+		let mut offset = range.start.max(0);
+		while offset < end {
+			let run = self.get_or_create_attribute_run(item, offset).await?;
+			// guard against runs which do not move forward, so that this always ends
+			if run.end <= offset {
+				break;
+			}
+			offset = run.end;
+			runs.push(run);
+		}
+		Ok(runs)
+	}
+
+	/// Forget the runs of text attributes of `item`, so that they are fetched again next time they are needed.
+	/// This should be called whenever the text of the item or its attributes change.
+	/// # Errors
+	/// Fails if a write lock can not be aquired on the item; see [`Self::modify_item`].
+	pub fn remove_attribute_runs(&self, item: &CacheKey) -> OdiliaResult<()> {
+		self.modify_item(item, |cache_item| cache_item.attribute_runs.clear())?;
+		Ok(())
+	}
+
+	/// Forget the embedded objects within the text of `item`, so that they are fetched again next time they are needed.
+	/// This should be called whenever the text or the children of the item change, since either moves the objects.
+	pub fn remove_hyperlinks(&self, item: &CacheKey) {
+		self.hyperlinks.remove(item);
+	}
+
 	/// Populate children and parent references given a cache and an `Arc<RwLock<CacheItem>>`.
 	/// This will unlock the `RwLock<_>`, update the references for children and parents, then go to the parent and children and do the same: update the parent for the children, then update the children referneces for the parent.
 	/// # Errors
@@ -901,6 +1183,7 @@ pub async fn accessible_to_cache_item(
 		states,
 		text,
 		children: children.into_iter().map(|k| CacheRef::new(k.into())).collect(),
+		attribute_runs: Vec::new(),
 		cache,
 	})
 }
@@ -1,4 +1,7 @@
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+use atspi_common::Role;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum ElementType {
 	Heading,
 	HeadingLevel1,
@@ -17,4 +20,46 @@ pub enum ElementType {
 	Audio,
 	Link,
 	Tab, // This is when you're looking at tabs in a dialog
+	Landmark,
+}
+
+impl ElementType {
+	/// The accessible role an item must have to be considered this type of element.
+	#[must_use]
+	pub fn role(self) -> Role {
+		match self {
+			Self::Heading
+			| Self::HeadingLevel1
+			| Self::HeadingLevel2
+			| Self::HeadingLevel3
+			| Self::HeadingLevel4
+			| Self::HeadingLevel5
+			| Self::HeadingLevel6 => Role::Heading,
+			Self::Button => Role::PushButton,
+			Self::Text => Role::Text,
+			Self::Table => Role::Table,
+			Self::TableCell => Role::TableCell,
+			Self::List => Role::List,
+			Self::ListItem => Role::ListItem,
+			Self::Video => Role::Video,
+			Self::Audio => Role::Audio,
+			Self::Link => Role::Link,
+			Self::Tab => Role::PageTab,
+			Self::Landmark => Role::Landmark,
+		}
+	}
+
+	/// The heading level this element type is restricted to, if any.
+	#[must_use]
+	pub fn level(self) -> Option<u8> {
+		match self {
+			Self::HeadingLevel1 => Some(1),
+			Self::HeadingLevel2 => Some(2),
+			Self::HeadingLevel3 => Some(3),
+			Self::HeadingLevel4 => Some(4),
+			Self::HeadingLevel5 => Some(5),
+			Self::HeadingLevel6 => Some(6),
+			_ => None,
+		}
+	}
 }
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::modes::ScreenReaderMode;
+use crate::{
+	elements::ElementType,
+	modes::ScreenReaderMode,
+	settings::{PunctuationLevel, VerbosityLevel},
+};
 use atspi_common::Role;
 
 #[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
@@ -19,6 +23,109 @@ pub enum Direction {
 	Backward,
 }
 
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", content = "args", rename_all = "camelCase")]
+/// Actions available while a navigable list (like the elements list) is open.
+pub enum ListAction {
+	/// Move to the next entry in the list.
+	Next,
+	/// Move to the previous entry in the list.
+	Previous,
+	/// Move to the first entry in the list.
+	First,
+	/// Move to the last entry in the list.
+	Last,
+	/// Move to the next entry which starts with the given letter, wrapping around at the end of the list.
+	FirstLetter(char),
+	/// Focus the item the current entry refers to, then close the list.
+	Activate,
+	/// Close the list without doing anything.
+	Close,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+/// Moves of the navigator object through the accessibility tree, independent of focus.
+pub enum ObjectAction {
+	Parent,
+	FirstChild,
+	NextSibling,
+	PreviousSibling,
+	/// Read the navigator object again.
+	Current,
+	/// Perform the default action of the navigator object (usually a click).
+	Activate,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "cursor", rename_all = "camelCase")]
+/// Which position in the text a command acts on.
+pub enum TextCursor {
+	/// The caret in the focused text.
+	Caret,
+	/// The review cursor.
+	Review,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "unit", rename_all = "camelCase")]
+/// How far the review cursor moves, and how much of the text under it is read.
+pub enum ReviewUnit {
+	Character,
+	Word,
+	Line,
+	/// A whole accessible object.
+	Object,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "key", rename_all = "camelCase")]
+/// A key which moves the caret in editable text, passed through to the application.
+/// Whatever handles the keyboard sends these over the input socket (as in `{"event": "caretKey", "args": {"key": "word"}}`) so that the caret movement which follows can be read at the right granularity.
+/// When none is sent, the granularity is guessed from the movement within the text instead.
+pub enum CaretKey {
+	/// Left or right arrow.
+	Character,
+	/// Control with left or right arrow.
+	Word,
+	/// Up or down arrow.
+	Line,
+	/// Control with up or down arrow.
+	Paragraph,
+	/// Home or end.
+	LineEdge,
+	/// Control with home or end.
+	DocumentEdge,
+	/// Page up or page down.
+	Page,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+/// Movement and reading commands within a table.
+pub enum TableAction {
+	/// Move to the next cell, continuing onto the start of the next row at the end of a row.
+	NextCell,
+	/// Move to the previous cell, continuing onto the end of the previous row at the start of a row.
+	PreviousCell,
+	/// Move down one row, staying in the same column.
+	NextRow,
+	/// Move up one row, staying in the same column.
+	PreviousRow,
+	/// Move right one column, staying in the same row.
+	NextColumn,
+	/// Move left one column, staying in the same row.
+	PreviousColumn,
+	/// Move to the top-left cell of the table.
+	FirstCell,
+	/// Move to the bottom-right cell of the table.
+	LastCell,
+	/// Read every cell in the current row.
+	ReadRow,
+	/// Read every cell in the current column.
+	ReadColumn,
+}
+
 #[derive(Eq, PartialEq, Clone, Hash, Serialize, Deserialize)]
 #[serde(tag = "event", content = "args", rename_all = "camelCase")]
 /// Events which can be trigged through Odilia's external API.
@@ -35,4 +142,48 @@ pub enum ScreenReaderEvent {
 	/// Change mode of the screen reader. This is currently global, but it should be per application, and an update should only affect the current application.
 	ChangeMode(ScreenReaderMode),
 	StructuralNavigation(Direction, Role),
+	/// Collect every element of a type in the current document and open them as a navigable list.
+	ElementsList(ElementType),
+	/// Navigate within the currently open list.
+	ListNavigation(ListAction),
+	/// Move around, or read part of, the table containing the current item.
+	TableNavigation(TableAction),
+	/// Move the review cursor through the contents of the active window, without moving focus or the caret.
+	ReviewMove(Direction, ReviewUnit),
+	/// Read the character, word, line or object under the review cursor.
+	ReviewCurrent(ReviewUnit),
+	/// Move the review cursor to the focused item (and the caret, if it has one).
+	RouteReviewToFocus,
+	/// Move focus (and the caret, if possible) to the review cursor.
+	RouteFocusToReview,
+	/// Move the navigator object around the accessibility tree, or activate it.
+	ObjectNavigation(ObjectAction),
+	/// Describe the focused item and where it is. Sent twice in quick succession, a more detailed description is given.
+	WhereAmI,
+	/// Read an older (backward) or newer (forward) alert from the history of alerts and notifications.
+	AlertHistory(Direction),
+	/// Read the most recent value of any progress bar.
+	ReadProgress,
+	/// Read all of the text selected in the focused item.
+	ReadSelection,
+	/// A caret movement key was passed through to the application.
+	CaretKey(CaretKey),
+	/// Change how much punctuation is spoken.
+	Punctuation(PunctuationLevel),
+	/// Read the user's pronunciation dictionaries again.
+	ReloadPronunciation,
+	/// Switch to another verbosity level, and keep it for next time.
+	Verbosity(VerbosityLevel),
+	/// Read the formatting (font, weight, colour and so on) of the text at the caret.
+	ReadFormatting,
+	/// Move the caret to the next or previous spelling error in the focused text.
+	SpellingError(Direction),
+	/// Spell the word at the caret or review cursor letter by letter.
+	SpellWord(TextCursor),
+	/// Read the character at the caret or review cursor phonetically, as in "b, bravo".
+	PhoneticCharacter(TextCursor),
+	/// Read the Unicode code point of the character at the caret or review cursor.
+	CharacterCodePoint(TextCursor),
+	/// Read the line and column of the caret in the focused text.
+	LineAndColumn,
 }
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+///structure for the configuration of alerts and notifications
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AlertSettings {
+	/// How many of the most recent alerts are kept for review; at least one is always kept.
+	pub history_length: usize,
+}
+impl AlertSettings {
+	/// Read the `[alerts]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self { history_length: ini.get("alerts", "history_length").unwrap_or(20) }
+	}
+}
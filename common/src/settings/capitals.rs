@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// How a capital letter is told apart from a lowercase one when it is read on its own.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapitalIndication {
+	/// Capitals are read like any other letter.
+	None,
+	/// Capitals are read at a higher pitch.
+	Pitch,
+	/// Capitals are read with "cap" before them.
+	Prefix,
+	/// Speech dispatcher plays a sound icon before capitals.
+	Icon,
+}
+serde_plain::derive_fromstr_from_deserialize!(CapitalIndication);
+
+///structure for the configuration of capital letter indication
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CapitalSettings {
+	pub indication: CapitalIndication,
+	/// How much higher capitals are read with the `pitch` indication, from 1 to 100.
+	pub pitch: i8,
+	/// Whether capitals are indicated when spelling a word, as well as when reading single characters.
+	pub spelling: bool,
+}
+impl CapitalSettings {
+	/// Read the `[capitals]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self {
+			indication: ini
+				.get("capitals", "indication")
+				.unwrap_or(CapitalIndication::Pitch),
+			pitch: ini.get("capitals", "pitch").unwrap_or(30),
+			spelling: ini.get("capitals", "spelling").unwrap_or(true),
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// What is said when typing into, or deleting from, a password field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordEcho {
+	/// Say "star" for each character.
+	Star,
+	/// Say nothing.
+	Silent,
+}
+serde_plain::derive_fromstr_from_deserialize!(PasswordEcho);
+
+///structure for the configuration of typing echo in editable text
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct EchoSettings {
+	/// Whether each typed character is spoken.
+	pub characters: bool,
+	/// Whether a word is spoken once it is finished, by typing a space or punctuation after it.
+	pub words: bool,
+	/// Whether deleted text is spoken.
+	pub deletions: bool,
+	pub passwords: PasswordEcho,
+}
+impl EchoSettings {
+	/// Read the `[echo]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self {
+			characters: ini.get("echo", "characters").unwrap_or(true),
+			words: ini.get("echo", "words").unwrap_or(false),
+			deletions: ini.get("echo", "deletions").unwrap_or(true),
+			passwords: ini.get("echo", "passwords").unwrap_or(PasswordEcho::Star),
+		}
+	}
+}
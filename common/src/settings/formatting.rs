@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+///structure for the configuration of text formatting announcements
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct FormattingSettings {
+	/// Whether changes in formatting (bold, font, colour and so on) are spoken as the caret moves into text formatted differently.
+	pub announce_changes: bool,
+	/// Whether misspelled words are pointed out when reading by word or line, and when a word is echoed after typing it.
+	pub spelling_errors: bool,
+}
+impl FormattingSettings {
+	/// Read the `[formatting]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self {
+			announce_changes: ini
+				.get("formatting", "announce_changes")
+				.unwrap_or(false),
+			spelling_errors: ini.get("formatting", "spelling_errors").unwrap_or(true),
+		}
+	}
+}
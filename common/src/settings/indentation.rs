@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// How indentation is announced when the caret moves onto a line indented differently from the one before it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentationMode {
+	/// Indentation is not announced.
+	Off,
+	/// The indentation is spoken as a count of tabs and spaces, e.g. "1 tab 2 spaces".
+	Speech,
+	/// A short tone is played, as a speech dispatcher sound icon, whose pitch rises with the indentation level.
+	Tone,
+	/// Every line is read at a speech pitch which rises with its indentation.
+	Pitch,
+}
+serde_plain::derive_fromstr_from_deserialize!(IndentationMode);
+
+///structure for the configuration of indentation announcements
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct IndentationSettings {
+	pub mode: IndentationMode,
+	/// How many spaces a tab counts as when working out the indentation level for the `tone` and `pitch` modes.
+	pub tab_width: usize,
+}
+impl IndentationSettings {
+	/// Read the `[indentation]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self {
+			mode: ini.get("indentation", "mode").unwrap_or(IndentationMode::Off),
+			tab_width: ini.get("indentation", "tab_width").unwrap_or(4),
+		}
+	}
+}
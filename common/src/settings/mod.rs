@@ -1,7 +1,27 @@
+mod alerts;
+mod capitals;
+mod echo;
+mod formatting;
+mod indentation;
 mod log;
+mod progress;
+mod punctuation;
 mod speech;
+mod states;
+mod templates;
+mod verbosity;
+pub use alerts::AlertSettings;
+pub use capitals::{CapitalIndication, CapitalSettings};
+pub use echo::{EchoSettings, PasswordEcho};
+pub use formatting::FormattingSettings;
+pub use indentation::{IndentationMode, IndentationSettings};
 use log::LogSettings;
+pub use progress::{ProgressScope, ProgressSettings};
+pub use punctuation::{PunctuationLevel, PunctuationSettings};
 use speech::SpeechSettings;
+pub use states::{StateScope, StateSettings, StateVerbosity};
+pub use templates::{TemplateField, TemplateSettings};
+pub use verbosity::{VerbosityLevel, VerbosityProfile, VerbositySettings};
 
 use serde::{Deserialize, Serialize};
 use tini::Ini;
@@ -15,6 +35,16 @@ use crate::errors::ConfigError;
 pub struct ApplicationConfig {
 	speech: SpeechSettings,
 	log: LogSettings,
+	states: StateSettings,
+	alerts: AlertSettings,
+	progress: ProgressSettings,
+	echo: EchoSettings,
+	punctuation: PunctuationSettings,
+	templates: TemplateSettings,
+	verbosity: VerbositySettings,
+	formatting: FormattingSettings,
+	capitals: CapitalSettings,
+	indentation: IndentationSettings,
 }
 
 impl ApplicationConfig {
@@ -29,7 +59,30 @@ impl ApplicationConfig {
 		let level: String = ini.get("log", "level").ok_or(ConfigError::ValueNotFound)?;
 		let speech = SpeechSettings::new(rate);
 		let log = LogSettings::new(level);
-		Ok(Self { speech, log })
+		let states = StateSettings::from_ini(&ini);
+		let alerts = AlertSettings::from_ini(&ini);
+		let progress = ProgressSettings::from_ini(&ini);
+		let echo = EchoSettings::from_ini(&ini);
+		let punctuation = PunctuationSettings::from_ini(&ini);
+		let templates = TemplateSettings::from_ini(&ini);
+		let verbosity = VerbositySettings::from_ini(&ini);
+		let formatting = FormattingSettings::from_ini(&ini);
+		let capitals = CapitalSettings::from_ini(&ini);
+		let indentation = IndentationSettings::from_ini(&ini);
+		Ok(Self {
+			speech,
+			log,
+			states,
+			alerts,
+			progress,
+			echo,
+			punctuation,
+			templates,
+			verbosity,
+			formatting,
+			capitals,
+			indentation,
+		})
 	}
 
 	#[must_use]
@@ -41,4 +94,54 @@ impl ApplicationConfig {
 	pub fn speech(&self) -> &SpeechSettings {
 		&self.speech
 	}
+
+	#[must_use]
+	pub fn states(&self) -> &StateSettings {
+		&self.states
+	}
+
+	#[must_use]
+	pub fn alerts(&self) -> &AlertSettings {
+		&self.alerts
+	}
+
+	#[must_use]
+	pub fn progress(&self) -> &ProgressSettings {
+		&self.progress
+	}
+
+	#[must_use]
+	pub fn echo(&self) -> &EchoSettings {
+		&self.echo
+	}
+
+	#[must_use]
+	pub fn punctuation(&self) -> &PunctuationSettings {
+		&self.punctuation
+	}
+
+	#[must_use]
+	pub fn templates(&self) -> &TemplateSettings {
+		&self.templates
+	}
+
+	#[must_use]
+	pub fn verbosity(&self) -> &VerbositySettings {
+		&self.verbosity
+	}
+
+	#[must_use]
+	pub fn formatting(&self) -> &FormattingSettings {
+		&self.formatting
+	}
+
+	#[must_use]
+	pub fn capitals(&self) -> &CapitalSettings {
+		&self.capitals
+	}
+
+	#[must_use]
+	pub fn indentation(&self) -> &IndentationSettings {
+		&self.indentation
+	}
 }
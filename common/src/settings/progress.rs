@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// Which progress bars are announced.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressScope {
+	/// Only those in the current window.
+	Window,
+	/// Those in any window.
+	All,
+}
+serde_plain::derive_fromstr_from_deserialize!(ProgressScope);
+
+///structure for the configuration of progress bar announcements
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ProgressSettings {
+	pub scope: ProgressScope,
+	/// After this many milliseconds since the last announcement for the same progress bar, any change is announced; 0 to announce every change.
+	pub interval: u64,
+	/// A change of at least this many percent since the last announcement for the same progress bar is announced straight away, whatever the interval; 0 to announce every change.
+	pub step: f64,
+	/// Whether sliders and spin buttons are announced like progress bars when their values change.
+	pub include_sliders: bool,
+}
+impl ProgressSettings {
+	/// Read the `[progress]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self {
+			scope: ini.get("progress", "scope").unwrap_or(ProgressScope::Window),
+			interval: ini.get("progress", "interval").unwrap_or(3000),
+			step: ini.get("progress", "step").unwrap_or(10.0),
+			include_sliders: ini.get("progress", "include_sliders").unwrap_or(false),
+		}
+	}
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tini::Ini;
+
+/// How much punctuation is spoken; each level includes everything spoken at the levels before it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PunctuationLevel {
+	/// Only symbols which change the meaning of the text, like currency signs.
+	None,
+	/// Symbols which are rarely used as punctuation, like "@" and "&".
+	Some,
+	/// Most punctuation, except for the common sentence punctuation.
+	Most,
+	/// Every symbol in the dictionary.
+	All,
+}
+serde_plain::derive_fromstr_from_deserialize!(PunctuationLevel);
+
+///structure for the configuration of punctuation, with a level for all applications and overrides for individual applications
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PunctuationSettings {
+	pub level: PunctuationLevel,
+	/// Levels for individual applications, by application name.
+	pub applications: HashMap<String, PunctuationLevel>,
+}
+impl PunctuationSettings {
+	/// Read the `[punctuation]` and `[punctuation_applications]` sections; any missing or invalid key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		let applications = ini
+			.section_iter("punctuation_applications")
+			.filter_map(|(application, level)| {
+				Some((application.clone(), level.parse().ok()?))
+			})
+			.collect();
+		Self {
+			level: ini.get("punctuation", "level").unwrap_or(PunctuationLevel::Some),
+			applications,
+		}
+	}
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// How much is said when a state of an item changes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateVerbosity {
+	/// Say nothing.
+	Off,
+	/// Only say the new state, e.g. "checked".
+	Brief,
+	/// Say the name of the item along with the new state, e.g. "Remember me, checked".
+	Full,
+}
+serde_plain::derive_fromstr_from_deserialize!(StateVerbosity);
+
+/// Which items state changes are announced for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateScope {
+	/// Only the focused item.
+	Focused,
+	/// Any item, focused or not.
+	All,
+}
+serde_plain::derive_fromstr_from_deserialize!(StateScope);
+
+///structure for the configuration of state change announcements, one verbosity for each state that can be announced
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct StateSettings {
+	pub scope: StateScope,
+	pub checked: StateVerbosity,
+	pub expanded: StateVerbosity,
+	pub selected: StateVerbosity,
+	pub pressed: StateVerbosity,
+	pub invalid: StateVerbosity,
+}
+impl StateSettings {
+	/// Read the `[states]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		let verbosity = |key| ini.get("states", key).unwrap_or(StateVerbosity::Brief);
+		Self {
+			scope: ini.get("states", "scope").unwrap_or(StateScope::Focused),
+			checked: verbosity("checked"),
+			expanded: verbosity("expanded"),
+			selected: verbosity("selected"),
+			pressed: verbosity("pressed"),
+			invalid: verbosity("invalid"),
+		}
+	}
+}
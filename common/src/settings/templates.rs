@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tini::Ini;
+
+/// A piece of information about an item which a speech template can include.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateField {
+	Name,
+	/// The localized name of the role, e.g. "push button".
+	Role,
+	/// States worth mentioning, e.g. "checked, required".
+	States,
+	/// The current value of a slider, progress bar or the like, or the text of a single-line editable field.
+	Value,
+	/// The position within a group, e.g. "3 of 7", and the level within a tree.
+	Position,
+	Description,
+	/// The keyboard shortcut which activates the item.
+	Shortcut,
+}
+serde_plain::derive_fromstr_from_deserialize!(TemplateField);
+
+/// The key of the template used for any role without a template of its own.
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+/// The templates shipped with odilia, by role name.
+const DEFAULT_TEMPLATES: [(&str, &str); 11] = [
+	(DEFAULT_TEMPLATE, "name role value states position description"),
+	("push button", "name role states shortcut description"),
+	("check box", "name role states shortcut description"),
+	("radio button", "name role states position shortcut"),
+	("link", "name role states description"),
+	("entry", "name role states value description"),
+	("slider", "name role value states"),
+	("menu item", "name states shortcut position"),
+	("list item", "name states position"),
+	("page tab", "name role states position"),
+	("tree item", "name states position"),
+];
+
+/// Parse a template: a list of fields, separated by spaces or commas. Returns `None` if any field is not known.
+fn parse(template: &str) -> Option<Vec<TemplateField>> {
+	template.split(|c: char| c == ',' || c.is_whitespace())
+		.filter(|field| !field.is_empty())
+		.map(|field| field.parse().ok())
+		.collect()
+}
+
+///structure for the configuration of speech templates: for each role, the fields spoken when an item with that role is focused, in order
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct TemplateSettings {
+	templates: HashMap<String, Vec<TemplateField>>,
+}
+impl TemplateSettings {
+	/// Read the `[templates]` section, where each key is a role name (e.g. `push button`) or `default`.
+	/// Templates in the section replace the ones shipped with odilia; invalid templates are ignored.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		let mut templates: HashMap<String, Vec<TemplateField>> = DEFAULT_TEMPLATES
+			.iter()
+			.filter_map(|(role, template)| {
+				Some(((*role).to_string(), parse(template)?))
+			})
+			.collect();
+		for (role, template) in ini.section_iter("templates") {
+			if let Some(template) = parse(template) {
+				templates.insert(role.clone(), template);
+			}
+		}
+		Self { templates }
+	}
+	/// The template for a role, by its name, falling back on the default template.
+	#[must_use]
+	pub fn template(&self, role: &str) -> &[TemplateField] {
+		self.templates
+			.get(role)
+			.or_else(|| self.templates.get(DEFAULT_TEMPLATE))
+			.map_or(&[], Vec::as_slice)
+	}
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use tini::Ini;
+
+/// How much is said about each item; see [`VerbosityProfile`] for what each level includes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerbosityLevel {
+	/// Everything, including hints on how to use each item.
+	Beginner,
+	/// Everything except hints.
+	Standard,
+	/// Only names, values and states; state changes are always spoken briefly.
+	Expert,
+}
+serde_plain::derive_fromstr_from_deserialize!(VerbosityLevel);
+
+/// What is spoken at a verbosity level.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct VerbosityProfile {
+	/// Whether the role of an item is spoken, e.g. "push button".
+	pub roles: bool,
+	/// Whether hints on how to use an item are spoken, e.g. "press space to activate".
+	pub hints: bool,
+	/// Whether the description of an item is spoken when it is focused, e.g. "Saves the file".
+	pub descriptions: bool,
+	/// Whether the states of an item are spoken when it is focused.
+	pub states: bool,
+	/// Whether the position of an item within its group is spoken, e.g. "3 of 7".
+	pub positions: bool,
+	/// Whether state changes set to `full` verbosity include the name of the item; if not, they are spoken briefly.
+	pub state_names: bool,
+}
+
+impl VerbosityLevel {
+	#[must_use]
+	pub fn profile(self) -> VerbosityProfile {
+		match self {
+			Self::Beginner => VerbosityProfile {
+				roles: true,
+				hints: true,
+				descriptions: true,
+				states: true,
+				positions: true,
+				state_names: true,
+			},
+			Self::Standard => VerbosityProfile {
+				roles: true,
+				hints: false,
+				descriptions: true,
+				states: true,
+				positions: true,
+				state_names: true,
+			},
+			Self::Expert => VerbosityProfile {
+				roles: false,
+				hints: false,
+				descriptions: false,
+				states: true,
+				positions: false,
+				state_names: false,
+			},
+		}
+	}
+}
+
+///structure for the configuration of verbosity
+#[derive(Debug, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct VerbositySettings {
+	pub level: VerbosityLevel,
+}
+impl VerbositySettings {
+	/// Read the `[verbosity]` section; any missing key falls back to its default.
+	#[must_use]
+	pub fn from_ini(ini: &Ini) -> Self {
+		Self { level: ini.get("verbosity", "level").unwrap_or(VerbosityLevel::Standard) }
+	}
+}
@@ -1,5 +1,6 @@
 use atspi_common::Granularity;
 use serde::{self, Deserialize, Serialize};
+use std::{convert::Infallible, str::FromStr};
 use zbus::zvariant::OwnedObjectPath;
 
 pub type Accessible = (String, OwnedObjectPath);
@@ -27,4 +28,18 @@ pub enum AriaLive {
 	Other(String),
 }
 
+impl FromStr for AriaLive {
+	type Err = Infallible;
+
+	/// Parse the value of a `live` (or `container-live`) object attribute; unknown values become [`AriaLive::Other`].
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		Ok(match value {
+			"off" => Self::Off,
+			"assertive" => Self::Assertive,
+			"polite" => Self::Polite,
+			other => Self::Other(other.to_string()),
+		})
+	}
+}
+
 pub type AriaAtomic = bool;
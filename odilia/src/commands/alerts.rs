@@ -0,0 +1,176 @@
+use crate::{
+	speech::embedded::{self, EMBEDDED_OBJECT},
+	state::ScreenReaderState,
+};
+use atspi_common::Role;
+use atspi_proxies::accessible::Accessible;
+use odilia_cache::CacheItem;
+use odilia_common::{events::Direction, result::OdiliaResult};
+use ssip_client_async::Priority;
+use std::{
+	collections::VecDeque,
+	time::{Duration, Instant},
+};
+
+/// The same alert often arrives through more than one event (e.g. window creation and a children changed event); repeats within this time are ignored.
+const REPEAT_WINDOW: Duration = Duration::from_secs(2);
+
+/// Roles which are spoken as alerts as soon as they appear, regardless of focus.
+pub const ALERT_ROLES: [Role; 2] = [Role::Alert, Role::Notification];
+
+/// The most recent alerts, newest last, and the position of the user while reviewing them.
+#[derive(Debug)]
+pub struct AlertHistory {
+	entries: VecDeque<(String, Instant)>,
+	position: Option<usize>,
+	/// How many alerts are kept for review.
+	length: usize,
+}
+
+impl AlertHistory {
+	/// An empty history keeping the last `length` alerts, or at least one.
+	pub fn new(length: usize) -> Self {
+		let length = length.max(1);
+		Self { entries: VecDeque::with_capacity(length), position: None, length }
+	}
+	/// Record an alert, returning false if it repeats the last one.
+	/// Reviewing starts over from the newest alert after each new one.
+	pub fn push(&mut self, text: String, at: Instant) -> bool {
+		if let Some((last, last_at)) = self.entries.back() {
+			if *last == text && at.duration_since(*last_at) < REPEAT_WINDOW {
+				return false;
+			}
+		}
+		if self.entries.len() == self.length {
+			self.entries.pop_front();
+		}
+		self.entries.push_back((text, at));
+		self.position = None;
+		true
+	}
+	/// Move through the history: backward is towards older alerts.
+	/// The first move backward lands on the newest alert. Returns `None` at either end.
+	pub fn step(&mut self, direction: &Direction) -> Option<(usize, &str)> {
+		let last = self.entries.len().checked_sub(1)?;
+		let next = match (direction, self.position) {
+			(Direction::Backward, None) => last,
+			(Direction::Backward, Some(position)) => position.checked_sub(1)?,
+			(Direction::Forward, None) => return None,
+			(Direction::Forward, Some(position)) => {
+				Some(position + 1).filter(|next| *next <= last)?
+			}
+		};
+		self.position = Some(next);
+		Some((next, self.entries[next].0.as_str()))
+	}
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+}
+
+/// The text of an alert: its name, or failing that, the text of it and its cached descendants.
+/// Links and other embedded objects are read where they sit within the text of their parent, rather than again on their own.
+/// # Errors
+/// Fails if the name can not be fetched.
+pub async fn alert_text(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<String> {
+	let name = item.name().await?;
+	if !name.trim().is_empty() {
+		return Ok(name);
+	}
+	let mut texts = Vec::new();
+	for part in std::iter::once(item.clone()).chain(state.cache.get_descendants(&item.object)) {
+		let embedded_in_parent = part.object != item.object
+			&& state.cache
+				.get(&part.parent.key)
+				.map_or(false, |parent| parent.text.contains(EMBEDDED_OBJECT));
+		if embedded_in_parent {
+			continue;
+		}
+		let text = embedded::expand_item(&part, &part.text, 0).await;
+		if !text.trim().is_empty() {
+			texts.push(text.trim().to_string());
+		}
+	}
+	Ok(texts.join(" "))
+}
+
+/// Speak `text` as an alert, and add it to the history.
+pub async fn alert(state: &ScreenReaderState, text: String) {
+	if text.trim().is_empty() {
+		return;
+	}
+	if !state.alerts.lock().await.push(text.clone(), Instant::now()) {
+		tracing::trace!("Ignoring a repeated alert.");
+		return;
+	}
+	state.say(Priority::Important, text).await;
+}
+
+/// Read an older or newer alert from the history.
+pub async fn review(state: &ScreenReaderState, direction: &Direction) {
+	let mut alerts = state.alerts.lock().await;
+	let total = alerts.len();
+	let text = match alerts.step(direction) {
+		Some((idx, text)) => format!("{text}, {} of {total}", idx + 1),
+		None if total == 0 => "No alerts".to_string(),
+		None => match direction {
+			Direction::Backward => "Oldest alert".to_string(),
+			Direction::Forward => "Newest alert".to_string(),
+		},
+	};
+	drop(alerts);
+	state.say(Priority::Text, text).await;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::AlertHistory;
+	use odilia_common::events::Direction;
+	use std::time::{Duration, Instant};
+
+	#[test]
+	fn repeats_are_ignored() {
+		let mut history = AlertHistory::new(20);
+		let now = Instant::now();
+		assert!(history.push("Saved".to_string(), now));
+		assert!(!history.push("Saved".to_string(), now + Duration::from_millis(500)));
+		assert!(history.push("Saved".to_string(), now + Duration::from_secs(5)));
+		assert_eq!(history.len(), 2);
+	}
+	#[test]
+	fn oldest_alerts_are_dropped() {
+		let mut history = AlertHistory::new(20);
+		let now = Instant::now();
+		for idx in 0..=20 {
+			history.push(format!("alert {idx}"), now);
+		}
+		assert_eq!(history.len(), 20);
+		for _ in 1..20 {
+			history.step(&Direction::Backward);
+		}
+		assert_eq!(history.step(&Direction::Backward), Some((0, "alert 1")));
+	}
+	#[test]
+	fn stepping_through_history() {
+		let mut history = AlertHistory::new(20);
+		let now = Instant::now();
+		assert_eq!(history.step(&Direction::Backward), None);
+		history.push("first".to_string(), now);
+		history.push("second".to_string(), now);
+		assert_eq!(history.step(&Direction::Forward), None);
+		assert_eq!(history.step(&Direction::Backward), Some((1, "second")));
+		assert_eq!(history.step(&Direction::Backward), Some((0, "first")));
+		assert_eq!(history.step(&Direction::Backward), None);
+		assert_eq!(history.step(&Direction::Forward), Some((1, "second")));
+		assert_eq!(history.step(&Direction::Forward), None);
+	}
+	#[test]
+	fn history_length_is_at_least_one() {
+		let mut history = AlertHistory::new(0);
+		let now = Instant::now();
+		history.push("first".to_string(), now);
+		history.push("second".to_string(), now);
+		assert_eq!(history.len(), 1);
+		assert_eq!(history.step(&Direction::Backward), Some((0, "second")));
+	}
+}
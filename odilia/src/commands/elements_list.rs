@@ -0,0 +1,249 @@
+use crate::state::ScreenReaderState;
+use atspi_client::convertable::Convertable;
+use atspi_common::{Role, ScrollType};
+use atspi_proxies::accessible::Accessible;
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::{
+	elements::ElementType, errors::CacheError, events::ListAction, modes::ScreenReaderMode,
+	result::OdiliaResult,
+};
+use ssip_client_async::Priority;
+
+/// The name of the mode Odilia switches to while a list is open.
+pub const LIST_MODE: &str = "ListMode";
+
+/// Roles which mark the root of a document. Elements are only collected up to the closest one of these.
+const DOCUMENT_ROLES: [Role; 6] = [
+	Role::DocumentWeb,
+	Role::DocumentFrame,
+	Role::DocumentText,
+	Role::DocumentEmail,
+	Role::DocumentSpreadsheet,
+	Role::DocumentPresentation,
+];
+
+/// A single entry in an [`ElementsList`]: what is spoken, and the item to focus when it is activated.
+#[derive(Clone, Debug)]
+pub struct ListEntry {
+	pub object: AccessiblePrimitive,
+	pub label: String,
+}
+
+/// A list of elements which is currently open, along with the mode to return to once it closes.
+#[derive(Debug)]
+pub struct ElementsList {
+	pub entries: Vec<ListEntry>,
+	pub index: usize,
+	pub previous_mode: ScreenReaderMode,
+}
+
+impl ElementsList {
+	fn current(&self) -> Option<&ListEntry> {
+		self.entries.get(self.index)
+	}
+	fn describe_current(&self) -> String {
+		match self.current() {
+			Some(entry) => format!(
+				"{}, {} of {}",
+				entry.label,
+				self.index + 1,
+				self.entries.len()
+			),
+			None => "Empty list".to_string(),
+		}
+	}
+}
+
+/// Find the root of the document containing `item`.
+/// If `item` is not inside a document, the top-most ancestor below the application is used instead, which is usually the window.
+pub fn document_root(state: &ScreenReaderState, item: CacheItem) -> CacheItem {
+	let ancestors = state.cache.get_ancestors(&item.object);
+	if DOCUMENT_ROLES.contains(&item.role) {
+		return item;
+	}
+	if let Some(document) = ancestors.iter().find(|a| DOCUMENT_ROLES.contains(&a.role)) {
+		return document.clone();
+	}
+	ancestors
+		.into_iter()
+		.filter(|ancestor| ancestor.role != Role::Application)
+		.last()
+		.unwrap_or(item)
+}
+
+/// Find the index of the next entry (after `current`) whose label starts with `letter`, wrapping around to the start of the list.
+/// The comparison is case insensitive, and leading whitespace in the label is ignored.
+pub fn find_by_first_letter(entries: &[ListEntry], current: usize, letter: char) -> Option<usize> {
+	let letter = letter.to_lowercase().collect::<String>();
+	(1..=entries.len())
+		.map(|offset| (current + offset) % entries.len())
+		.find(|&idx| entries[idx].label.trim_start().to_lowercase().starts_with(&letter))
+}
+
+async fn heading_level(item: &CacheItem) -> Option<u8> {
+	item.get_attributes().await.ok()?.get("level")?.parse().ok()
+}
+
+async fn label_for(item: &CacheItem) -> OdiliaResult<String> {
+	let text = item.text.trim();
+	if text.is_empty() {
+		item.name().await
+	} else {
+		Ok(text.to_string())
+	}
+}
+
+/// Collect every element of `element_type` in the document containing the currently focused item, in document order.
+async fn collect(
+	state: &ScreenReaderState,
+	element_type: ElementType,
+) -> OdiliaResult<Vec<ListEntry>> {
+	let current = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let current = state.cache.get(&current).ok_or(CacheError::NoItem)?;
+	let root = document_root(state, current);
+	let mut entries = Vec::new();
+	for item in state.cache.get_descendants(&root.object) {
+		if item.role != element_type.role() {
+			continue;
+		}
+		let mut label = label_for(&item).await?;
+		if item.role == Role::Heading {
+			let level = heading_level(&item).await;
+			if element_type.level().is_some() && level != element_type.level() {
+				continue;
+			}
+			if let Some(level) = level {
+				label = format!("{label}, level {level}");
+			}
+		}
+		entries.push(ListEntry { object: item.object, label });
+	}
+	Ok(entries)
+}
+
+/// Open a list of every element of `element_type` in the current document, and switch into [`LIST_MODE`].
+/// # Errors
+/// Fails if there is no focused item to find a document from, or if the labels for the elements can not be fetched.
+pub async fn open(state: &ScreenReaderState, element_type: ElementType) -> OdiliaResult<()> {
+	let role = element_type.role();
+	let entries = collect(state, element_type).await?;
+	if entries.is_empty() {
+		state.say(Priority::Text, format!("No {role}s")).await;
+		return Ok(());
+	}
+	let mut mode = state.mode.lock().await;
+	let previous_mode = std::mem::replace(&mut *mode, ScreenReaderMode::new(LIST_MODE));
+	drop(mode);
+	let list = ElementsList { entries, index: 0, previous_mode };
+	let summary = format!("{} {role}s. {}", list.entries.len(), list.describe_current());
+	*state.elements_list.lock().await = Some(list);
+	state.say(Priority::Text, summary).await;
+	Ok(())
+}
+
+/// Close the open list (if any) and return to the mode Odilia was in before it was opened.
+pub async fn close(state: &ScreenReaderState) -> Option<ElementsList> {
+	let list = state.elements_list.lock().await.take()?;
+	*state.mode.lock().await = list.previous_mode.clone();
+	Some(list)
+}
+
+async fn activate(state: &ScreenReaderState, entry: &ListEntry) -> OdiliaResult<()> {
+	let accessible = entry.object.clone().into_accessible(state.connection()).await?;
+	let component = accessible.to_component().await?;
+	let _: bool = component.grab_focus().await?;
+	let _: bool = component.scroll_to(ScrollType::TopLeft).await?;
+	state.update_accessible(entry.object.clone()).await;
+	Ok(())
+}
+
+/// Act on the open list. Does nothing if no list is open.
+/// # Errors
+/// Fails if activating an entry fails; the list is closed either way.
+pub async fn navigate(state: &ScreenReaderState, action: ListAction) -> OdiliaResult<()> {
+	let mut list_lock = state.elements_list.lock().await;
+	let Some(list) = list_lock.as_mut() else {
+		tracing::debug!("List navigation requested, but no list is open.");
+		return Ok(());
+	};
+	let last_index = list.entries.len().saturating_sub(1);
+	match action {
+		ListAction::Next => list.index = (list.index + 1).min(last_index),
+		ListAction::Previous => list.index = list.index.saturating_sub(1),
+		ListAction::First => list.index = 0,
+		ListAction::Last => list.index = last_index,
+		ListAction::FirstLetter(letter) => {
+			if let Some(idx) = find_by_first_letter(&list.entries, list.index, letter) {
+				list.index = idx;
+			} else {
+				state.say(
+					Priority::Text,
+					format!("No items starting with {letter}"),
+				)
+				.await;
+				return Ok(());
+			}
+		}
+		ListAction::Activate | ListAction::Close => {
+			drop(list_lock);
+			let Some(closed) = close(state).await else {
+				return Ok(());
+			};
+			if action == ListAction::Close {
+				state.say(Priority::Text, "List closed".to_string()).await;
+				return Ok(());
+			}
+			return match closed.current() {
+				Some(entry) => activate(state, entry).await,
+				None => Ok(()),
+			};
+		}
+	}
+	let description = list.describe_current();
+	drop(list_lock);
+	state.say(Priority::Text, description).await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{find_by_first_letter, ListEntry};
+	use odilia_cache::AccessiblePrimitive;
+
+	fn entries(labels: &[&str]) -> Vec<ListEntry> {
+		labels.iter()
+			.enumerate()
+			.map(|(idx, label)| ListEntry {
+				object: AccessiblePrimitive {
+					id: format!("/org/a11y/atspi/accessible/{idx}"),
+					sender: ":1.2".into(),
+				},
+				label: (*label).to_string(),
+			})
+			.collect()
+	}
+
+	#[test]
+	fn first_letter_finds_next_match() {
+		let list = entries(&["Apples", "Bananas", "Blueberries", "Cherries"]);
+		assert_eq!(find_by_first_letter(&list, 0, 'b'), Some(1));
+		assert_eq!(find_by_first_letter(&list, 1, 'b'), Some(2));
+	}
+	#[test]
+	fn first_letter_wraps_around() {
+		let list = entries(&["Apples", "Bananas", "Blueberries", "Cherries"]);
+		assert_eq!(find_by_first_letter(&list, 2, 'B'), Some(1));
+		assert_eq!(find_by_first_letter(&list, 3, 'a'), Some(0));
+	}
+	#[test]
+	fn first_letter_ignores_leading_whitespace() {
+		let list = entries(&["Apples", "  cherries"]);
+		assert_eq!(find_by_first_letter(&list, 0, 'C'), Some(1));
+	}
+	#[test]
+	fn first_letter_no_match() {
+		let list = entries(&["Apples", "Bananas"]);
+		assert_eq!(find_by_first_letter(&list, 0, 'z'), None);
+		assert_eq!(find_by_first_letter(&[], 0, 'a'), None);
+	}
+}
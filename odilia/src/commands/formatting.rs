@@ -0,0 +1,146 @@
+//! Speaking the formatting of text: its font, weight, style, colour and so on, as given by the text attributes of the run it is in.
+
+use crate::state::ScreenReaderState;
+use odilia_cache::CacheItem;
+use odilia_common::{errors::CacheError, result::OdiliaResult};
+use ssip_client_async::Priority;
+use std::{collections::HashMap, sync::atomic::Ordering};
+
+/// The text attributes which are spoken, in the order they are spoken.
+const ATTRIBUTES: [&str; 8] = [
+	"family-name",
+	"size",
+	"weight",
+	"style",
+	"underline",
+	"strikethrough",
+	"fg-color",
+	"invalid",
+];
+
+/// How a single text attribute is spoken, or `None` if the value is not worth mentioning (like a normal weight).
+fn spoken(name: &str, value: &str) -> Option<String> {
+	let spoken = match (name, value) {
+		("family-name", font) => font.to_string(),
+		("size", size) => format!("{} point", size.trim_end_matches("pt")),
+		("weight", "bold") => "bold".to_string(),
+		("weight", weight)
+			if weight.parse::<u32>().map_or(false, |weight| weight >= 600) =>
+		{
+			"bold".to_string()
+		}
+		("style", "italic" | "oblique") => "italic".to_string(),
+		("underline", underline) if !["none", "false"].contains(&underline) => {
+			"underlined".to_string()
+		}
+		("strikethrough", "true") => "strikethrough".to_string(),
+		("fg-color", colour) => format!("colour {colour}"),
+		("invalid", "spelling") => "spelling error".to_string(),
+		("invalid", "grammar") => "grammar error".to_string(),
+		_ => return None,
+	};
+	(!value.is_empty()).then_some(spoken)
+}
+
+fn spoken_attribute(attributes: &HashMap<String, String>, name: &str) -> Option<String> {
+	spoken(name, attributes.get(name)?)
+}
+
+/// Describe a set of text attributes, e.g. "Cantarell, 11 point, bold".
+pub fn describe(attributes: &HashMap<String, String>) -> Vec<String> {
+	ATTRIBUTES
+		.iter()
+		.filter_map(|name| spoken_attribute(attributes, name))
+		.collect()
+}
+
+/// Describe what changed between two sets of text attributes; attributes which went away are spoken with "not", as in "not bold".
+pub fn changes(old: &HashMap<String, String>, new: &HashMap<String, String>) -> Vec<String> {
+	ATTRIBUTES
+		.iter()
+		.filter_map(|name| {
+			match (spoken_attribute(old, name), spoken_attribute(new, name)) {
+				(old, new) if old == new => None,
+				(_, Some(new)) => Some(new),
+				(Some(old), None) => Some(format!("not {old}")),
+				(None, None) => None,
+			}
+		})
+		.collect()
+}
+
+/// Read the formatting of the text at the caret.
+/// # Errors
+/// Fails if the focused item is not cached, or its attributes can not be fetched.
+pub async fn read(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let offset = state.previous_caret_position.load(Ordering::Relaxed);
+	let run = state.cache.get_or_create_attribute_run(&focused, offset).await?;
+	let description = describe(&run.attributes);
+	let text = if description.is_empty() {
+		"No formatting".to_string()
+	} else {
+		description.join(", ")
+	};
+	state.say(Priority::Text, text).await;
+	Ok(())
+}
+
+/// Speak whatever changed in the formatting since the caret was last in a different run of text, if the user wants changes announced.
+/// # Errors
+/// Fails if the attributes at the caret can not be fetched.
+pub async fn caret_moved(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	offset: i32,
+) -> OdiliaResult<()> {
+	if !state.config.formatting().announce_changes {
+		return Ok(());
+	}
+	let run = state.cache.get_or_create_attribute_run(&item.object, offset).await?;
+	let mut last = state.caret_attributes.lock().await;
+	let changes = changes(&last, &run.attributes);
+	*last = run.attributes;
+	drop(last);
+	if !changes.is_empty() {
+		state.say(Priority::Text, changes.join(", ")).await;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{changes, describe};
+	use std::collections::HashMap;
+
+	fn attributes(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+		pairs.iter()
+			.map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn describing() {
+		let run = attributes(&[
+			("weight", "700"),
+			("family-name", "Cantarell"),
+			("size", "11pt"),
+			("style", "normal"),
+			("underline", "none"),
+			("invalid", "spelling"),
+			("language", "en-GB"),
+		]);
+		assert_eq!(describe(&run), ["Cantarell", "11 point", "bold", "spelling error"]);
+		assert!(describe(&attributes(&[("weight", "400")])).is_empty());
+	}
+	#[test]
+	fn changed_attributes() {
+		let plain = attributes(&[("family-name", "Cantarell"), ("weight", "400")]);
+		let bold = attributes(&[("family-name", "Cantarell"), ("weight", "bold")]);
+		let underlined = attributes(&[("family-name", "Sans"), ("underline", "single")]);
+		assert_eq!(changes(&plain, &bold), ["bold"]);
+		assert_eq!(changes(&bold, &plain), ["not bold"]);
+		assert_eq!(changes(&bold, &underlined), ["Sans", "not bold", "underlined"]);
+		assert!(changes(&bold, &bold).is_empty());
+	}
+}
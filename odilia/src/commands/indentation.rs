@@ -0,0 +1,172 @@
+//! Indentation and line numbers, for editing code.
+
+use crate::{
+	commands::where_am_i::line_and_column, events::caret::paragraph_at, speech,
+	state::ScreenReaderState,
+};
+use odilia_cache::CacheItem;
+use odilia_common::{
+	errors::CacheError,
+	result::OdiliaResult,
+	settings::{IndentationMode, IndentationSettings},
+};
+use ssip_client_async::{tokio::Request as SSIPRequest, Priority};
+use std::sync::atomic::Ordering;
+
+/// The deepest indentation level with a tone of its own; deeper lines share its tone.
+const DEEPEST_TONE: usize = 10;
+
+/// The whitespace at the start of a line.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Indentation {
+	pub tabs: usize,
+	pub spaces: usize,
+}
+
+impl Indentation {
+	/// The indentation of `line`.
+	pub fn of(line: &str) -> Self {
+		line.chars().take_while(|c| *c == ' ' || *c == '\t').fold(
+			Self::default(),
+			|indentation, c| match c {
+				'\t' => Self { tabs: indentation.tabs + 1, ..indentation },
+				_ => Self { spaces: indentation.spaces + 1, ..indentation },
+			},
+		)
+	}
+	/// How the indentation is spoken, e.g. "1 tab 2 spaces".
+	pub fn describe(self) -> String {
+		let count = |n: usize, name: &str| match n {
+			0 => None,
+			1 => Some(format!("1 {name}")),
+			n => Some(format!("{n} {name}s")),
+		};
+		let parts: Vec<String> = [count(self.tabs, "tab"), count(self.spaces, "space")]
+			.into_iter()
+			.flatten()
+			.collect();
+		if parts.is_empty() {
+			"no indentation".to_string()
+		} else {
+			parts.join(" ")
+		}
+	}
+	/// How many columns the indentation takes up.
+	fn columns(self, tab_width: usize) -> usize {
+		self.tabs * tab_width + self.spaces
+	}
+	/// The pitch a line with this indentation is read at with the `pitch` mode: each column raises it a little, up to the highest pitch.
+	pub fn pitch(self, tab_width: usize) -> i8 {
+		i8::try_from((self.columns(tab_width) * 4).min(100)).unwrap_or(i8::MAX)
+	}
+	/// The sound icon played for this indentation with the `tone` mode: one per level of a tab's width, each higher than the last.
+	pub fn tone(self, tab_width: usize) -> String {
+		let level = (self.columns(tab_width) / tab_width.max(1)).min(DEEPEST_TONE);
+		format!("odilia-indent-{level}.wav")
+	}
+}
+
+/// Work out what to do about the indentation of the line at `offset` in `item`, after the caret moved there from another line.
+/// With the `speech` and `tone` modes, the indentation is spoken or its tone played here if it differs from the previous line.
+/// With the `pitch` mode, the pitch to read the line at is returned.
+pub async fn line_entered(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	offset: usize,
+) -> Option<i8> {
+	let IndentationSettings { mode, tab_width } = state.config.indentation();
+	if *mode == IndentationMode::Off {
+		return None;
+	}
+	let (line, _) = paragraph_at(&item.text, offset);
+	let indentation = Indentation::of(&line);
+	let previous = state.last_indentation.lock().await.replace(indentation);
+	match mode {
+		IndentationMode::Speech if previous != Some(indentation) => {
+			state.say(Priority::Text, indentation.describe()).await;
+			None
+		}
+		IndentationMode::Tone if previous != Some(indentation) => {
+			play_tone(state, &indentation.tone(*tab_width)).await;
+			None
+		}
+		IndentationMode::Pitch => Some(indentation.pitch(*tab_width)),
+		_ => None,
+	}
+}
+
+/// Play the sound icon `icon`, ahead of the line it belongs to.
+async fn play_tone(state: &ScreenReaderState, icon: &str) -> bool {
+	if state.ssip
+		.send(SSIPRequest::SetPriority(Priority::Text))
+		.await
+		.is_err()
+	{
+		return false;
+	}
+	// the client has no request for sound icons, so the command is sent as it is
+	state.ssip
+		.send(SSIPRequest::SendLine(format!("SOUND_ICON {icon}")))
+		.await
+		.is_ok()
+}
+
+/// Read the line and column of the caret in the focused text.
+/// # Errors
+/// Fails if the focused item is not cached.
+pub async fn read_line_and_column(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let item = state.cache.get(&focused).ok_or(CacheError::NoItem)?;
+	let offset = usize::try_from(state.previous_caret_position.load(Ordering::Relaxed))?;
+	let (line, column) = line_and_column(&item.text, offset);
+	state.say(Priority::Text, format!("line {line}, column {column}"))
+		.await;
+	Ok(())
+}
+
+/// Speak `text`, the line the caret moved onto, at the pitch for its indentation if there is one.
+pub async fn say_line(state: &ScreenReaderState, text: String, pitch: Option<i8>) {
+	match pitch {
+		Some(pitch) => {
+			speech::say_at_pitch(state, Priority::Text, text, pitch).await;
+		}
+		None => {
+			state.say(Priority::Text, text).await;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Indentation;
+
+	#[test]
+	fn measuring_indentation() {
+		assert_eq!(Indentation::of("fn main() {"), Indentation::default());
+		assert_eq!(Indentation::of("    let x = 1;"), Indentation { tabs: 0, spaces: 4 });
+		assert_eq!(Indentation::of("\t\t  // x"), Indentation { tabs: 2, spaces: 2 });
+		assert_eq!(Indentation::of("   "), Indentation { tabs: 0, spaces: 3 });
+	}
+	#[test]
+	fn describing_indentation() {
+		assert_eq!(Indentation::default().describe(), "no indentation");
+		assert_eq!(Indentation { tabs: 1, spaces: 0 }.describe(), "1 tab");
+		assert_eq!(Indentation { tabs: 2, spaces: 1 }.describe(), "2 tabs 1 space");
+		assert_eq!(Indentation { tabs: 0, spaces: 8 }.describe(), "8 spaces");
+	}
+	#[test]
+	fn pitch_rises_with_indentation() {
+		assert_eq!(Indentation::default().pitch(4), 0);
+		assert_eq!(Indentation { tabs: 1, spaces: 2 }.pitch(4), 24);
+		assert_eq!(Indentation { tabs: 10, spaces: 0 }.pitch(8), 100);
+	}
+	#[test]
+	fn tone_follows_indentation_level() {
+		assert_eq!(Indentation::default().tone(4), "odilia-indent-0.wav");
+		assert_eq!(Indentation { tabs: 0, spaces: 3 }.tone(4), "odilia-indent-0.wav");
+		assert_eq!(Indentation { tabs: 1, spaces: 4 }.tone(4), "odilia-indent-2.wav");
+		assert_eq!(Indentation { tabs: 2, spaces: 0 }.tone(8), "odilia-indent-2.wav");
+		assert_eq!(Indentation { tabs: 30, spaces: 0 }.tone(4), "odilia-indent-10.wav");
+		assert_eq!(Indentation { tabs: 0, spaces: 2 }.tone(0), "odilia-indent-2.wav");
+	}
+}
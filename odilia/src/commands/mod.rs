@@ -0,0 +1,14 @@
+//! Handlers for [`odilia_common::events::ScreenReaderEvent`]s which need more than a line or two of logic.
+//! Each module covers one feature, and is called from [`crate::events::sr_event`].
+
+pub mod alerts;
+pub mod elements_list;
+pub mod formatting;
+pub mod indentation;
+pub mod object_navigation;
+pub mod progress;
+pub mod review;
+pub mod selection;
+pub mod spelling;
+pub mod table;
+pub mod where_am_i;
@@ -0,0 +1,155 @@
+use crate::state::ScreenReaderState;
+use atspi_client::convertable::Convertable;
+use atspi_common::Role;
+use atspi_proxies::accessible::Accessible;
+use odilia_cache::CacheItem;
+use odilia_common::{errors::CacheError, events::ObjectAction, result::OdiliaResult};
+use ssip_client_async::Priority;
+
+/// The path AT-SPI uses when an object has no parent.
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+/// Find the index of the sibling `action` moves to, given the number of children of the parent.
+/// Returns `None` for actions which do not move between siblings, or when there is no sibling in that direction.
+pub fn sibling_index(action: ObjectAction, current: usize, siblings: usize) -> Option<usize> {
+	match action {
+		ObjectAction::NextSibling => Some(current + 1).filter(|next| *next < siblings),
+		ObjectAction::PreviousSibling => current.checked_sub(1),
+		_ => None,
+	}
+}
+
+/// Build what is spoken when the navigator lands on an object.
+pub fn summary(name: &str, role: &str, children: i32) -> String {
+	let mut parts = Vec::new();
+	if !name.trim().is_empty() {
+		parts.push(name.trim().to_string());
+	}
+	parts.push(role.to_string());
+	match children {
+		i32::MIN..=0 => {}
+		1 => parts.push("1 child".to_string()),
+		n => parts.push(format!("{n} children")),
+	}
+	parts.join(", ")
+}
+
+/// The navigator object, which follows focus until it is moved explicitly.
+async fn navigator(state: &ScreenReaderState) -> OdiliaResult<CacheItem> {
+	let current = match state.navigator.lock().await.clone() {
+		Some(navigator) => navigator,
+		None => state.history_item(0).await.ok_or(CacheError::NoItem)?,
+	};
+	state.get_or_create_cache_item(current).await
+}
+
+async fn parent_of(state: &ScreenReaderState, item: &mut CacheItem) -> OdiliaResult<CacheItem> {
+	match item.parent_ref() {
+		Ok(parent) => Ok(parent.read()?.clone()),
+		// the parent has not been cached yet
+		Err(_) => state.get_or_create_cache_item(item.parent.key.clone()).await,
+	}
+}
+
+async fn children_of(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<Vec<CacheItem>> {
+	if let Ok(children) = item.get_children() {
+		return Ok(children);
+	}
+	// some of the children are not cached yet
+	let mut children = Vec::with_capacity(item.children.len());
+	for child in &item.children {
+		children.push(state.get_or_create_cache_item(child.key.clone()).await?);
+	}
+	Ok(children)
+}
+
+/// Find the object `action` moves the navigator to, or `None` if there is nothing there.
+async fn target(
+	state: &ScreenReaderState,
+	mut item: CacheItem,
+	action: ObjectAction,
+) -> OdiliaResult<Option<CacheItem>> {
+	match action {
+		ObjectAction::Parent => {
+			if item.role == Role::Application || item.parent.key.id == NULL_PATH {
+				return Ok(None);
+			}
+			Ok(Some(parent_of(state, &mut item).await?))
+		}
+		ObjectAction::FirstChild => Ok(children_of(state, &item).await?.into_iter().next()),
+		ObjectAction::NextSibling | ObjectAction::PreviousSibling => {
+			let parent = parent_of(state, &mut item).await?;
+			let siblings = children_of(state, &parent).await?;
+			let Some(current) =
+				siblings.iter().position(|sibling| sibling.object == item.object)
+			else {
+				return Ok(None);
+			};
+			Ok(sibling_index(action, current, siblings.len())
+				.and_then(|idx| siblings.into_iter().nth(idx)))
+		}
+		ObjectAction::Current | ObjectAction::Activate => Ok(Some(item)),
+	}
+}
+
+async fn speak_summary(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<()> {
+	let name = if item.text.trim().is_empty() { item.name().await? } else { item.text.clone() };
+	let role = item.get_localized_role_name().await?;
+	state.say(Priority::Text, summary(&name, &role, item.children_num))
+		.await;
+	Ok(())
+}
+
+/// Move the navigator object, read it, or perform its default action.
+/// Moving the navigator does not move focus, so objects which can not be focused can still be reached.
+/// # Errors
+/// Fails if there is no navigator object or focused item to start from, or if the `DBus` calls to describe or activate the object fail.
+pub async fn navigate(state: &ScreenReaderState, action: ObjectAction) -> OdiliaResult<()> {
+	let item = navigator(state).await?;
+	if action == ObjectAction::Activate {
+		let actions = item
+			.object
+			.clone()
+			.into_accessible(state.connection())
+			.await?
+			.to_action()
+			.await?;
+		if !actions.do_action(0).await? {
+			state.say(Priority::Text, "No action".to_string()).await;
+		}
+		return Ok(());
+	}
+	let Some(next) = target(state, item, action).await? else {
+		let edge = match action {
+			ObjectAction::Parent => "No parent",
+			ObjectAction::FirstChild => "No children",
+			ObjectAction::NextSibling => "No next object",
+			_ => "No previous object",
+		};
+		state.say(Priority::Text, edge.to_string()).await;
+		return Ok(());
+	};
+	*state.navigator.lock().await = Some(next.object.clone());
+	speak_summary(state, &next).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{sibling_index, summary};
+	use odilia_common::events::ObjectAction;
+
+	#[test]
+	fn siblings_stop_at_the_ends() {
+		assert_eq!(sibling_index(ObjectAction::NextSibling, 0, 3), Some(1));
+		assert_eq!(sibling_index(ObjectAction::NextSibling, 2, 3), None);
+		assert_eq!(sibling_index(ObjectAction::PreviousSibling, 2, 3), Some(1));
+		assert_eq!(sibling_index(ObjectAction::PreviousSibling, 0, 3), None);
+		assert_eq!(sibling_index(ObjectAction::Parent, 1, 3), None);
+	}
+	#[test]
+	fn summary_includes_children() {
+		assert_eq!(summary("OK", "push button", 0), "OK, push button");
+		assert_eq!(summary("", "panel", 1), "panel, 1 child");
+		assert_eq!(summary(" Tools ", "tool bar", 4), "Tools, tool bar, 4 children");
+	}
+}
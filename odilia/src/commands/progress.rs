@@ -0,0 +1,164 @@
+use crate::{commands::review::window_of, state::ScreenReaderState};
+use atspi_client::convertable::Convertable;
+use atspi_common::Role;
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::{
+	result::OdiliaResult,
+	settings::{ProgressScope, ProgressSettings},
+};
+use ssip_client_async::Priority;
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+/// Roles whose value changes are always announced.
+const PROGRESS_ROLES: [Role; 1] = [Role::ProgressBar];
+/// Roles whose value changes are announced only if the user asks for it.
+const SLIDER_ROLES: [Role; 2] = [Role::Slider, Role::SpinButton];
+
+/// The last value announced for each progress bar, and the most recent value of any of them.
+#[derive(Debug, Default)]
+pub struct ProgressTracker {
+	announced: HashMap<AccessiblePrimitive, (f64, Instant)>,
+	latest: Option<f64>,
+}
+
+/// Convert a value into a percentage of its range, or `None` if the range is empty.
+pub fn percent(current: f64, minimum: f64, maximum: f64) -> Option<f64> {
+	let range = maximum - minimum;
+	(range > 0.0).then(|| ((current - minimum) / range * 100.0).clamp(0.0, 100.0))
+}
+
+/// Decide whether a new value should be announced, given the last one announced for the same progress bar.
+/// Completion is always announced; otherwise either the interval or the step from the settings must have passed.
+pub fn should_announce(
+	last: Option<(f64, Instant)>,
+	percent: f64,
+	now: Instant,
+	settings: &ProgressSettings,
+) -> bool {
+	let Some((last_percent, at)) = last else {
+		return true;
+	};
+	if (percent - last_percent).abs() < f64::EPSILON {
+		return false;
+	}
+	if percent >= 100.0 {
+		return true;
+	}
+	let waited = now.duration_since(at) >= Duration::from_millis(settings.interval);
+	let moved = (percent - last_percent).abs() >= settings.step;
+	waited || moved
+}
+
+/// Announce the new value of a progress bar (or slider, or spin button), subject to the user's settings.
+/// # Errors
+/// Fails if the item does not implement the `Value` interface.
+pub async fn value_changed(state: &ScreenReaderState, item: CacheItem) -> OdiliaResult<()> {
+	let settings = state.config.progress();
+	let announced_role = PROGRESS_ROLES.contains(&item.role)
+		|| (settings.include_sliders && SLIDER_ROLES.contains(&item.role));
+	if !announced_role {
+		return Ok(());
+	}
+	let value = item
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_value()
+		.await?;
+	let (current, minimum, maximum) = tokio::try_join!(
+		value.current_value(),
+		value.minimum_value(),
+		value.maximum_value()
+	)?;
+	let Some(percent) = percent(current, minimum, maximum) else {
+		return Ok(());
+	};
+	if PROGRESS_ROLES.contains(&item.role) {
+		state.progress.lock().await.latest = Some(percent);
+	}
+	if settings.scope == ProgressScope::Window {
+		let window = window_of(state, item.clone()).await;
+		let current_window = state.current_window.lock().await.clone();
+		if current_window.as_ref() != Some(&window.object) {
+			return Ok(());
+		}
+	}
+	let now = Instant::now();
+	let mut tracker = state.progress.lock().await;
+	let last = tracker.announced.get(&item.object).copied();
+	if !should_announce(last, percent, now, settings) {
+		return Ok(());
+	}
+	tracker.announced.insert(item.object, (percent, now));
+	drop(tracker);
+	state.say(Priority::Progress, format!("{percent:.0} percent")).await;
+	Ok(())
+}
+
+/// Read the most recent value of any progress bar.
+pub async fn read_latest(state: &ScreenReaderState) {
+	let latest = state.progress.lock().await.latest;
+	let text = match latest {
+		Some(percent) => format!("{percent:.0} percent"),
+		None => "No progress".to_string(),
+	};
+	state.say(Priority::Text, text).await;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{percent, should_announce};
+	use odilia_common::settings::{ProgressScope, ProgressSettings};
+	use std::time::{Duration, Instant};
+
+	fn settings() -> ProgressSettings {
+		ProgressSettings {
+			scope: ProgressScope::Window,
+			interval: 1000,
+			step: 10.0,
+			include_sliders: false,
+		}
+	}
+
+	#[test]
+	fn percent_of_range() {
+		assert_eq!(percent(5.0, 0.0, 10.0), Some(50.0));
+		assert_eq!(percent(150.0, 100.0, 200.0), Some(50.0));
+		assert_eq!(percent(1.0, 0.0, 0.0), None);
+	}
+	#[test]
+	fn first_value_is_announced() {
+		assert!(should_announce(None, 3.0, Instant::now(), &settings()));
+	}
+	#[test]
+	fn throttled_until_interval_or_step() {
+		let start = Instant::now();
+		let soon = start + Duration::from_millis(500);
+		assert!(!should_announce(Some((10.0, start)), 15.0, soon, &settings()));
+	}
+	#[test]
+	fn announced_once_interval_passed() {
+		let start = Instant::now();
+		let later = start + Duration::from_secs(2);
+		assert!(should_announce(Some((10.0, start)), 11.0, later, &settings()));
+		let every_change = ProgressSettings { interval: 0, step: 100.0, ..settings() };
+		assert!(should_announce(Some((10.0, start)), 11.0, start, &every_change));
+	}
+	#[test]
+	fn announced_once_step_reached() {
+		let start = Instant::now();
+		assert!(should_announce(Some((10.0, start)), 20.0, start, &settings()));
+		let every_change = ProgressSettings { interval: 60_000, step: 0.0, ..settings() };
+		assert!(should_announce(Some((10.0, start)), 11.0, start, &every_change));
+	}
+	#[test]
+	fn completion_is_always_announced() {
+		let start = Instant::now();
+		assert!(should_announce(Some((95.0, start)), 100.0, start, &settings()));
+		assert!(!should_announce(Some((100.0, start)), 100.0, start, &settings()));
+	}
+}
@@ -0,0 +1,407 @@
+use crate::{speech::embedded::EMBEDDED_OBJECT, state::ScreenReaderState};
+use atspi_client::convertable::Convertable;
+use atspi_common::{Role, ScrollType};
+use atspi_proxies::text::Text;
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::{
+	errors::CacheError,
+	events::{Direction, ReviewUnit},
+	result::OdiliaResult,
+};
+use ssip_client_async::Priority;
+
+/// Where the review cursor is: an object, and a character offset within its text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReviewCursor {
+	pub object: AccessiblePrimitive,
+	pub offset: usize,
+}
+
+/// A position within a [`ReviewModel`]: the index of an object, and a character offset within its text.
+pub type ReviewPosition = (usize, usize);
+
+/// A flattened view of every object with text in a window, in document order.
+#[derive(Debug, Default)]
+pub struct ReviewModel {
+	pub objects: Vec<AccessiblePrimitive>,
+	pub texts: Vec<Vec<char>>,
+}
+
+impl ReviewModel {
+	fn position_of(&self, cursor: &ReviewCursor) -> Option<ReviewPosition> {
+		let idx = self.objects.iter().position(|object| *object == cursor.object)?;
+		let last = self.texts[idx].len().saturating_sub(1);
+		Some((idx, cursor.offset.min(last)))
+	}
+}
+
+/// Whether an object has anything worth reviewing; whitespace and embedded object characters do not count.
+fn is_reviewable(text: &str) -> bool {
+	text.chars().any(|c| !c.is_whitespace() && c != EMBEDDED_OBJECT)
+}
+
+fn is_word_char(c: char) -> bool {
+	!c.is_whitespace() && c != EMBEDDED_OBJECT
+}
+
+/// Find the start and end (exclusive) of the `unit` surrounding `offset` in `text`.
+/// For words, if `offset` is not on a word, the returned range is empty.
+pub fn unit_bounds(text: &[char], offset: usize, unit: ReviewUnit) -> (usize, usize) {
+	let offset = offset.min(text.len());
+	match unit {
+		ReviewUnit::Character => (offset, (offset + 1).min(text.len())),
+		ReviewUnit::Word => {
+			if !text.get(offset).copied().map_or(false, is_word_char) {
+				return (offset, offset);
+			}
+			let start = text[..offset]
+				.iter()
+				.rposition(|c| !is_word_char(*c))
+				.map_or(0, |i| i + 1);
+			let end = text[offset..]
+				.iter()
+				.position(|c| !is_word_char(*c))
+				.map_or(text.len(), |i| offset + i);
+			(start, end)
+		}
+		ReviewUnit::Line => {
+			let start = text[..offset]
+				.iter()
+				.rposition(|c| *c == '\n')
+				.map_or(0, |i| i + 1);
+			let end = text[offset..]
+				.iter()
+				.position(|c| *c == '\n')
+				.map_or(text.len(), |i| offset + i);
+			(start, end)
+		}
+		ReviewUnit::Object => (0, text.len()),
+	}
+}
+
+/// The offset of the first word in `text` at or after `from`.
+fn first_word_from(text: &[char], from: usize) -> Option<usize> {
+	text.iter()
+		.skip(from)
+		.position(|c| is_word_char(*c))
+		.map(|i| from + i)
+}
+
+/// The start of the last word in `text` which begins before `before`.
+fn last_word_before(text: &[char], before: usize) -> Option<usize> {
+	let end = text[..before.min(text.len())]
+		.iter()
+		.rposition(|c| is_word_char(*c))?;
+	Some(unit_bounds(text, end, ReviewUnit::Word).0)
+}
+
+/// Calculate where the review cursor ends up after moving one `unit` in `direction` from `current`.
+/// Movement continues into the next (or previous) object when the current one runs out.
+/// Returns `None` at the top or bottom of the window.
+pub fn move_position(
+	texts: &[Vec<char>],
+	current: ReviewPosition,
+	direction: &Direction,
+	unit: ReviewUnit,
+) -> Option<ReviewPosition> {
+	let (idx, offset) = current;
+	let text = texts.get(idx)?;
+	let within = match (direction, unit) {
+		(_, ReviewUnit::Object) => None,
+		(Direction::Forward, ReviewUnit::Character) => {
+			Some(offset + 1).filter(|next| *next < text.len())
+		}
+		(Direction::Backward, ReviewUnit::Character) => offset.checked_sub(1),
+		(Direction::Forward, ReviewUnit::Word) => {
+			let (_, end) = unit_bounds(text, offset, ReviewUnit::Word);
+			first_word_from(text, end.max(offset + 1))
+		}
+		(Direction::Backward, ReviewUnit::Word) => {
+			let (start, _) = unit_bounds(text, offset, ReviewUnit::Word);
+			last_word_before(text, start)
+		}
+		(Direction::Forward, ReviewUnit::Line) => {
+			let (_, end) = unit_bounds(text, offset, ReviewUnit::Line);
+			Some(end + 1).filter(|next| *next < text.len())
+		}
+		(Direction::Backward, ReviewUnit::Line) => {
+			let (start, _) = unit_bounds(text, offset, ReviewUnit::Line);
+			start.checked_sub(1)
+				.map(|prev_end| unit_bounds(text, prev_end, ReviewUnit::Line).0)
+		}
+	};
+	if let Some(offset) = within {
+		return Some((idx, offset));
+	}
+	match direction {
+		Direction::Forward => (idx + 1..texts.len()).find_map(|next| {
+			let text = &texts[next];
+			let offset = match unit {
+				ReviewUnit::Word => first_word_from(text, 0)?,
+				_ => 0,
+			};
+			Some((next, offset))
+		}),
+		Direction::Backward => (0..idx).rev().find_map(|prev| {
+			let text = &texts[prev];
+			let offset = match unit {
+				ReviewUnit::Character => text.len().saturating_sub(1),
+				ReviewUnit::Word => last_word_before(text, text.len())?,
+				ReviewUnit::Line => {
+					unit_bounds(text, text.len(), ReviewUnit::Line).0
+				}
+				ReviewUnit::Object => 0,
+			};
+			Some((prev, offset))
+		}),
+	}
+}
+
+/// Describe a single character so that whitespace is not spoken as silence.
+fn describe_character(c: char) -> String {
+	match c {
+		' ' => "space".to_string(),
+		'\n' => "new line".to_string(),
+		'\t' => "tab".to_string(),
+		EMBEDDED_OBJECT => "embedded object".to_string(),
+		c => c.to_string(),
+	}
+}
+
+/// Build what should be spoken for the `unit` at `offset`.
+pub fn describe_unit(text: &[char], offset: usize, unit: ReviewUnit) -> String {
+	if unit == ReviewUnit::Character {
+		return text
+			.get(offset)
+			.map_or_else(|| "blank".to_string(), |c| describe_character(*c));
+	}
+	let (start, end) = unit_bounds(text, offset, unit);
+	let spoken: String = text[start..end].iter().filter(|c| **c != EMBEDDED_OBJECT).collect();
+	if spoken.trim().is_empty() {
+		"blank".to_string()
+	} else {
+		spoken
+	}
+}
+
+/// Find the window containing `item`: the top-most ancestor below the application.
+/// If not all of its ancestors are cached, the current window is used instead, as long as it belongs to the same application.
+pub async fn window_of(state: &ScreenReaderState, item: CacheItem) -> CacheItem {
+	let mut ancestors = state.cache.get_ancestors(&item.object);
+	let reaches_app = ancestors
+		.last()
+		.map_or(false, |ancestor| ancestor.role == Role::Application);
+	if !reaches_app {
+		if let Some(window) = state.current_window().await {
+			if window.object.sender == item.object.sender {
+				return window;
+			}
+		}
+	}
+	ancestors.retain(|ancestor| ancestor.role != Role::Application);
+	ancestors.pop().unwrap_or(item)
+}
+
+/// Flatten the window containing `item` into a [`ReviewModel`].
+async fn build_model(state: &ScreenReaderState, item: CacheItem) -> ReviewModel {
+	let window = window_of(state, item).await;
+	let mut model = ReviewModel::default();
+	for descendant in state.cache.get_descendants(&window.object) {
+		if is_reviewable(&descendant.text) {
+			model.texts.push(descendant.text.chars().collect());
+			model.objects.push(descendant.object);
+		}
+	}
+	model
+}
+
+/// Where the review cursor should be placed for the focused item: on its caret if it has one, otherwise at its start.
+async fn focus_cursor(state: &ScreenReaderState) -> OdiliaResult<ReviewCursor> {
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let item = state.cache.get(&focused).ok_or(CacheError::NoItem)?;
+	let offset = match item.caret_offset().await {
+		Ok(offset) => usize::try_from(offset).unwrap_or(0),
+		Err(_) => 0,
+	};
+	Ok(ReviewCursor { object: focused, offset })
+}
+
+/// Move the review cursor to the focused item, and read the line it lands on.
+/// # Errors
+/// Fails if nothing is focused, or the focused item is not cached.
+pub async fn route_to_focus(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let cursor = focus_cursor(state).await?;
+	let item = state.cache.get(&cursor.object).ok_or(CacheError::NoItem)?;
+	let text: Vec<char> = item.text.chars().collect();
+	let description = describe_unit(&text, cursor.offset, ReviewUnit::Line);
+	*state.review_cursor.lock().await = Some(cursor);
+	state.say(Priority::Text, description).await;
+	Ok(())
+}
+
+/// Move focus to the object under the review cursor, and the caret to the cursor's offset if the object has text.
+/// # Errors
+/// Fails if the review cursor has not been placed, or the object under it can not be focused.
+pub async fn route_focus(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let cursor = state.review_cursor.lock().await.clone().ok_or(CacheError::NoItem)?;
+	let item = state.cache.get(&cursor.object).ok_or(CacheError::NoItem)?;
+	let component = cursor
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_component()
+		.await?;
+	let _: bool = component.grab_focus().await?;
+	let _: bool = component.scroll_to(ScrollType::TopLeft).await?;
+	// not every object has a caret; focusing it is the best that can be done for those.
+	if let Ok(offset) = i32::try_from(cursor.offset) {
+		let _: bool = item.set_caret_offset(offset).await.unwrap_or(false);
+	}
+	state.update_accessible(cursor.object).await;
+	Ok(())
+}
+
+/// Read the `unit` under the review cursor, placing it on the focused item first if it has not been placed yet.
+/// # Errors
+/// Fails if the review cursor has not been placed and nothing is focused.
+pub async fn read_current(state: &ScreenReaderState, unit: ReviewUnit) -> OdiliaResult<()> {
+	let cursor = current_or_focus(state).await?;
+	let item = state.cache.get(&cursor.object).ok_or(CacheError::NoItem)?;
+	let text: Vec<char> = item.text.chars().collect();
+	state.say(Priority::Text, describe_unit(&text, cursor.offset, unit))
+		.await;
+	Ok(())
+}
+
+pub async fn current_or_focus(state: &ScreenReaderState) -> OdiliaResult<ReviewCursor> {
+	let mut cursor = state.review_cursor.lock().await;
+	if let Some(cursor) = cursor.as_ref() {
+		return Ok(cursor.clone());
+	}
+	let new = focus_cursor(state).await?;
+	*cursor = Some(new.clone());
+	Ok(new)
+}
+
+/// Move the review cursor one `unit` in `direction` through the window it is in, and read the `unit` it lands on.
+/// Neither focus nor the caret are moved.
+/// # Errors
+/// Fails if the review cursor has not been placed and nothing is focused.
+pub async fn move_cursor(
+	state: &ScreenReaderState,
+	direction: &Direction,
+	unit: ReviewUnit,
+) -> OdiliaResult<()> {
+	let cursor = current_or_focus(state).await?;
+	let item = state.cache.get(&cursor.object).ok_or(CacheError::NoItem)?;
+	let model = build_model(state, item).await;
+	// the cursor may be on an object with no text (e.g. an empty focused container); start from the first object then.
+	let current = model.position_of(&cursor).unwrap_or((0, 0));
+	let Some((idx, offset)) = move_position(&model.texts, current, direction, unit) else {
+		let edge = match direction {
+			Direction::Forward => "Bottom",
+			Direction::Backward => "Top",
+		};
+		state.say(Priority::Text, edge.to_string()).await;
+		return Ok(());
+	};
+	let description = describe_unit(&model.texts[idx], offset, unit);
+	*state.review_cursor.lock().await =
+		Some(ReviewCursor { object: model.objects[idx].clone(), offset });
+	state.say(Priority::Text, description).await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{describe_unit, move_position, unit_bounds};
+	use odilia_common::events::{Direction, ReviewUnit};
+
+	fn texts(objects: &[&str]) -> Vec<Vec<char>> {
+		objects.iter().map(|text| text.chars().collect()).collect()
+	}
+
+	#[test]
+	fn word_bounds() {
+		let text: Vec<char> = "hello big world".chars().collect();
+		assert_eq!(unit_bounds(&text, 7, ReviewUnit::Word), (6, 9));
+		assert_eq!(unit_bounds(&text, 5, ReviewUnit::Word), (5, 5));
+		assert_eq!(describe_unit(&text, 12, ReviewUnit::Word), "world");
+	}
+	#[test]
+	fn characters_cross_objects() {
+		let model = texts(&["ab", "c"]);
+		assert_eq!(
+			move_position(&model, (0, 1), &Direction::Forward, ReviewUnit::Character),
+			Some((1, 0))
+		);
+		assert_eq!(
+			move_position(&model, (1, 0), &Direction::Backward, ReviewUnit::Character),
+			Some((0, 1))
+		);
+		assert_eq!(
+			move_position(&model, (1, 0), &Direction::Forward, ReviewUnit::Character),
+			None
+		);
+	}
+	#[test]
+	fn words_within_and_across_objects() {
+		let model = texts(&["one  two", "three"]);
+		assert_eq!(
+			move_position(&model, (0, 1), &Direction::Forward, ReviewUnit::Word),
+			Some((0, 5))
+		);
+		assert_eq!(
+			move_position(&model, (0, 5), &Direction::Forward, ReviewUnit::Word),
+			Some((1, 0))
+		);
+		assert_eq!(
+			move_position(&model, (1, 2), &Direction::Backward, ReviewUnit::Word),
+			Some((0, 5))
+		);
+		assert_eq!(
+			move_position(&model, (0, 6), &Direction::Backward, ReviewUnit::Word),
+			Some((0, 0))
+		);
+		assert_eq!(
+			move_position(&model, (0, 0), &Direction::Backward, ReviewUnit::Word),
+			None
+		);
+	}
+	#[test]
+	fn lines_within_and_across_objects() {
+		let model = texts(&["first\nsecond", "third"]);
+		assert_eq!(
+			move_position(&model, (0, 2), &Direction::Forward, ReviewUnit::Line),
+			Some((0, 6))
+		);
+		assert_eq!(
+			move_position(&model, (0, 8), &Direction::Forward, ReviewUnit::Line),
+			Some((1, 0))
+		);
+		assert_eq!(
+			move_position(&model, (1, 3), &Direction::Backward, ReviewUnit::Line),
+			Some((0, 6))
+		);
+		assert_eq!(
+			move_position(&model, (0, 8), &Direction::Backward, ReviewUnit::Line),
+			Some((0, 0))
+		);
+		assert_eq!(describe_unit(&model[0], 8, ReviewUnit::Line), "second");
+	}
+	#[test]
+	fn objects_and_blank_units() {
+		let model = texts(&["a b", "c"]);
+		assert_eq!(
+			move_position(&model, (0, 2), &Direction::Forward, ReviewUnit::Object),
+			Some((1, 0))
+		);
+		assert_eq!(
+			move_position(&model, (1, 0), &Direction::Forward, ReviewUnit::Object),
+			None
+		);
+		assert_eq!(describe_unit(&model[0], 1, ReviewUnit::Character), "space");
+		assert_eq!(describe_unit(&model[0], 1, ReviewUnit::Word), "blank");
+	}
+}
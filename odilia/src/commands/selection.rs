@@ -0,0 +1,148 @@
+use crate::state::ScreenReaderState;
+use atspi_common::Interface;
+use atspi_proxies::text::Text;
+use odilia_cache::CacheItem;
+use odilia_common::{errors::CacheError, result::OdiliaResult};
+use ssip_client_async::Priority;
+
+/// A selected range of text, from its start offset up to (but not including) its end offset.
+pub type Range = (i32, i32);
+
+/// Sort ranges by their start, dropping empty ones and merging any which overlap or touch.
+pub fn normalise(ranges: &[Range]) -> Vec<Range> {
+	let mut sorted: Vec<Range> = ranges
+		.iter()
+		.map(|(start, end)| (*start.min(end), *start.max(end)))
+		.filter(|(start, end)| start < end)
+		.collect();
+	sorted.sort_unstable();
+	let mut merged: Vec<Range> = Vec::with_capacity(sorted.len());
+	for (start, end) in sorted {
+		match merged.last_mut() {
+			Some(last) if start <= last.1 => last.1 = last.1.max(end),
+			_ => merged.push((start, end)),
+		}
+	}
+	merged
+}
+
+/// The parts of `ranges` which are not covered by any of `minus`.
+/// Both must be normalised.
+fn subtract(ranges: &[Range], minus: &[Range]) -> Vec<Range> {
+	let mut remaining = Vec::new();
+	for (start, end) in ranges.iter().copied() {
+		let mut cursor = start;
+		for (other_start, other_end) in minus.iter().copied() {
+			if other_end <= cursor || other_start >= end {
+				continue;
+			}
+			if other_start > cursor {
+				remaining.push((cursor, other_start));
+			}
+			cursor = cursor.max(other_end);
+		}
+		if cursor < end {
+			remaining.push((cursor, end));
+		}
+	}
+	remaining
+}
+
+/// Compare two sets of selections, returning the ranges which were newly selected and those which were unselected.
+pub fn diff(old: &[Range], new: &[Range]) -> (Vec<Range>, Vec<Range>) {
+	let old = normalise(old);
+	let new = normalise(new);
+	(subtract(&new, &old), subtract(&old, &new))
+}
+
+/// Fetch every selection of `item`.
+/// # Errors
+/// Fails if the item does not implement the `Text` interface.
+pub async fn selections(item: &CacheItem) -> OdiliaResult<Vec<Range>> {
+	let count = item.get_nselections().await?;
+	let mut ranges = Vec::new();
+	for idx in 0..count {
+		ranges.push(item.get_selection(idx).await?);
+	}
+	Ok(normalise(&ranges))
+}
+
+async fn text_of(item: &CacheItem, ranges: &[Range]) -> String {
+	let mut texts = Vec::new();
+	for (start, end) in ranges.iter().copied() {
+		match item.get_text(start, end).await {
+			Ok(text) if !text.trim().is_empty() => texts.push(text),
+			Ok(_) => {}
+			Err(e) => {
+				tracing::debug!(error = %e, "Could not get the text of a selection.");
+			}
+		}
+	}
+	texts.join(" ")
+}
+
+/// Compare the selections of `item` with those it had last time, and speak what was selected or unselected.
+/// # Errors
+/// Fails if the selections can not be fetched.
+pub async fn selection_changed(state: &ScreenReaderState, item: CacheItem) -> OdiliaResult<()> {
+	let new = selections(&item).await?;
+	let old = state
+		.selections
+		.lock()
+		.await
+		.insert(item.object.clone(), new.clone())
+		.unwrap_or_default();
+	let (selected, unselected) = diff(&old, &new);
+	let unselected = text_of(&item, &unselected).await;
+	if !unselected.is_empty() {
+		state.say(Priority::Text, format!("unselected: {unselected}")).await;
+	}
+	let selected = text_of(&item, &selected).await;
+	if !selected.is_empty() {
+		state.say(Priority::Text, format!("selected: {selected}")).await;
+	}
+	Ok(())
+}
+
+/// Read everything selected in the focused item.
+/// # Errors
+/// Fails if nothing is focused, or if the selections can not be fetched.
+pub async fn read(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let item = state.get_or_create_cache_item(focused).await?;
+	let text = if item.interfaces.contains(Interface::Text) {
+		text_of(&item, &selections(&item).await?).await
+	} else {
+		String::new()
+	};
+	let text = if text.is_empty() { "No selection".to_string() } else { text };
+	state.say(Priority::Text, text).await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{diff, normalise};
+
+	#[test]
+	fn normalising_ranges() {
+		assert_eq!(normalise(&[(5, 3), (0, 0), (1, 4), (8, 9)]), vec![(1, 5), (8, 9)]);
+	}
+	#[test]
+	fn extending_a_selection() {
+		assert_eq!(diff(&[(0, 3)], &[(0, 5)]), (vec![(3, 5)], vec![]));
+		assert_eq!(diff(&[(2, 6)], &[(0, 6)]), (vec![(0, 2)], vec![]));
+	}
+	#[test]
+	fn shrinking_a_selection() {
+		assert_eq!(diff(&[(0, 5)], &[(0, 3)]), (vec![], vec![(3, 5)]));
+		assert_eq!(diff(&[(0, 5)], &[]), (vec![], vec![(0, 5)]));
+	}
+	#[test]
+	fn multiple_selections() {
+		assert_eq!(
+			diff(&[(0, 2), (10, 12)], &[(0, 2), (5, 7), (11, 12)]),
+			(vec![(5, 7)], vec![(10, 11)])
+		);
+	}
+}
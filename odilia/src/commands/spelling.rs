@@ -0,0 +1,254 @@
+//! Spelling: spelling out words and characters, and spelling errors, which word processors and browsers mark with the `invalid:spelling` text attribute.
+
+use crate::{
+	commands::review::{current_or_focus, unit_bounds},
+	events::echo::character_name,
+	speech::capitals,
+	state::ScreenReaderState,
+};
+use atspi_proxies::text::Text;
+use odilia_cache::{AttributeRun, CacheItem};
+use odilia_common::{
+	errors::CacheError,
+	events::{Direction, ReviewUnit, TextCursor},
+	result::OdiliaResult,
+};
+use ssip_client_async::Priority;
+use std::sync::atomic::Ordering;
+
+/// The word at the character offset `offset` of `text`, or the word just before it when the caret is right after a word.
+pub fn word_at(text: &[char], offset: usize) -> Option<String> {
+	let (start, end) = match unit_bounds(text, offset, ReviewUnit::Word) {
+		(start, end) if start < end => (start, end),
+		_ => unit_bounds(text, offset.checked_sub(1)?, ReviewUnit::Word),
+	};
+	(start < end).then(|| text[start..end].iter().collect())
+}
+
+/// How the code point of a character is read, as in "é, U+00E9".
+pub fn code_point(c: char) -> String {
+	format!("{}, U+{:04X}", character_name(c), u32::from(c))
+}
+
+/// The cached text at the caret or review cursor, and the character offset of the cursor within it.
+async fn text_at(
+	state: &ScreenReaderState,
+	cursor: TextCursor,
+) -> OdiliaResult<(Vec<char>, usize)> {
+	let (object, offset) = match cursor {
+		TextCursor::Caret => {
+			let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+			let offset = state.previous_caret_position.load(Ordering::Relaxed);
+			(focused, usize::try_from(offset)?)
+		}
+		TextCursor::Review => {
+			let review = current_or_focus(state).await?;
+			(review.object, review.offset)
+		}
+	};
+	let item = state.cache.get(&object).ok_or(CacheError::NoItem)?;
+	Ok((item.text.chars().collect(), offset))
+}
+
+/// Spell the word at the caret or review cursor, letter by letter.
+/// # Errors
+/// Fails if the item the cursor is in is not cached.
+pub async fn spell_word(state: &ScreenReaderState, cursor: TextCursor) -> OdiliaResult<()> {
+	let (text, offset) = text_at(state, cursor).await?;
+	match word_at(&text, offset) {
+		Some(word) => capitals::spell(state, &word).await,
+		None => {
+			state.say(Priority::Text, "No word".to_string()).await;
+		}
+	}
+	Ok(())
+}
+
+/// Read the character at the caret or review cursor phonetically, as in "b, bravo".
+/// # Errors
+/// Fails if the item the cursor is in is not cached.
+pub async fn phonetic(state: &ScreenReaderState, cursor: TextCursor) -> OdiliaResult<()> {
+	let (text, offset) = text_at(state, cursor).await?;
+	let Some(c) = text.get(offset).copied() else {
+		state.say(Priority::Text, "No character".to_string()).await;
+		return Ok(());
+	};
+	capitals::say_character(state, Priority::Text, character_name(c)).await;
+	if let Some(word) = state.phonetic.word(c) {
+		state.say(Priority::Text, word.to_string()).await;
+	}
+	Ok(())
+}
+
+/// Read the Unicode code point of the character at the caret or review cursor.
+/// # Errors
+/// Fails if the item the cursor is in is not cached.
+pub async fn read_code_point(state: &ScreenReaderState, cursor: TextCursor) -> OdiliaResult<()> {
+	let (text, offset) = text_at(state, cursor).await?;
+	let text = text
+		.get(offset)
+		.map_or_else(|| "No character".to_string(), |c| code_point(*c));
+	state.say(Priority::Text, text).await;
+	Ok(())
+}
+
+/// The ranges of text (start and end character offsets) marked as misspelled, in order.
+pub fn errors(runs: &[AttributeRun]) -> Vec<(i32, i32)> {
+	let mut errors: Vec<(i32, i32)> = runs
+		.iter()
+		.filter(|run| {
+			run.start < run.end
+				&& run.attributes
+					.get("invalid")
+					.map_or(false, |invalid| invalid == "spelling")
+		})
+		.map(|run| (run.start, run.end))
+		.collect();
+	errors.sort_unstable();
+	errors
+}
+
+/// The spelling error after (or before) the character offset `offset`.
+/// Going backward from within an error finds the start of that error.
+pub fn next(errors: &[(i32, i32)], offset: i32, direction: &Direction) -> Option<(i32, i32)> {
+	match direction {
+		Direction::Forward => errors.iter().find(|(start, _)| *start > offset).copied(),
+		Direction::Backward => {
+			errors.iter().rev().find(|(start, _)| *start < offset).copied()
+		}
+	}
+}
+
+/// Point out the misspelled words within `text`, which starts at the character offset `start` within its item.
+/// When the text is a single misspelled word, "misspelled" is enough; otherwise the words are named.
+pub fn indication(text: &str, start: i32, errors: &[(i32, i32)]) -> Option<String> {
+	let chars: Vec<char> = text.chars().collect();
+	let end = start + i32::try_from(chars.len()).ok()?;
+	let words: Vec<String> = errors
+		.iter()
+		.filter(|(error_start, error_end)| *error_start < end && *error_end > start)
+		.filter_map(|(error_start, error_end)| {
+			let from = usize::try_from(error_start.max(&start) - start).ok()?;
+			let to = usize::try_from(error_end.min(&end) - start).ok()?;
+			Some(chars[from..to].iter().collect::<String>().trim().to_string())
+		})
+		.filter(|word| !word.is_empty())
+		.collect();
+	match words.as_slice() {
+		[] => None,
+		[word] if word == text.trim() => Some("misspelled".to_string()),
+		words => Some(format!("misspelled {}", words.join(", "))),
+	}
+}
+
+/// Point out the misspelled words within `text`, part of the text of `item` starting at the character offset `start`, if the user wants them pointed out.
+/// Errors fetching the attributes of the item are logged, since the text itself has already been spoken.
+pub async fn indicate(state: &ScreenReaderState, item: &CacheItem, text: &str, start: i32) {
+	if !state.config.formatting().spelling_errors {
+		return;
+	}
+	let Ok(length) = i32::try_from(text.chars().count()) else {
+		return;
+	};
+	// only the runs under the text are needed, rather than those of the whole document
+	match state
+		.cache
+		.get_or_create_attribute_runs(&item.object, start..start + length)
+		.await
+	{
+		Ok(runs) => {
+			if let Some(indication) = indication(text, start, &errors(&runs)) {
+				state.say(Priority::Text, indication).await;
+			}
+		}
+		Err(e) => {
+			tracing::debug!(error = %e, "Could not get the spelling errors of an item");
+		}
+	}
+}
+
+/// Move the caret to the next (or previous) spelling error in the focused text; the caret movement reads the error.
+/// # Errors
+/// Fails if the focused item is not cached, or its attributes can not be fetched.
+pub async fn jump(state: &ScreenReaderState, direction: &Direction) -> OdiliaResult<()> {
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let item = state.cache.get(&focused).ok_or(CacheError::NoItem)?;
+	let offset = state.previous_caret_position.load(Ordering::Relaxed);
+	let length = i32::try_from(item.text.chars().count())?;
+	let runs = state.cache.get_or_create_attribute_runs(&focused, 0..length).await?;
+	let Some((start, _)) = next(&errors(&runs), offset, direction) else {
+		state.say(Priority::Text, "No more spelling errors".to_string()).await;
+		return Ok(());
+	};
+	let _: bool = item.set_caret_offset(start).await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{code_point, errors, indication, next, word_at};
+	use odilia_cache::AttributeRun;
+	use odilia_common::events::Direction;
+	use std::collections::HashMap;
+
+	fn run(start: i32, end: i32, invalid: Option<&str>) -> AttributeRun {
+		let attributes = invalid
+			.map(|invalid| {
+				HashMap::from([("invalid".to_string(), invalid.to_string())])
+			})
+			.unwrap_or_default();
+		AttributeRun { start, end, attributes }
+	}
+
+	// "I cant spel wel"
+	fn runs() -> Vec<AttributeRun> {
+		vec![
+			run(12, 15, Some("spelling")),
+			run(0, 2, None),
+			run(2, 6, Some("grammar")),
+			run(6, 7, None),
+			run(7, 11, Some("spelling")),
+			run(11, 12, None),
+		]
+	}
+
+	#[test]
+	fn finding_errors() {
+		assert_eq!(errors(&runs()), [(7, 11), (12, 15)]);
+		assert!(errors(&[run(3, 3, Some("spelling"))]).is_empty());
+	}
+	#[test]
+	fn next_and_previous_errors() {
+		let errors = errors(&runs());
+		assert_eq!(next(&errors, 0, &Direction::Forward), Some((7, 11)));
+		assert_eq!(next(&errors, 7, &Direction::Forward), Some((12, 15)));
+		assert_eq!(next(&errors, 12, &Direction::Forward), None);
+		assert_eq!(next(&errors, 14, &Direction::Backward), Some((12, 15)));
+		assert_eq!(next(&errors, 12, &Direction::Backward), Some((7, 11)));
+		assert_eq!(next(&errors, 7, &Direction::Backward), None);
+	}
+	#[test]
+	fn pointing_out_errors() {
+		let errors = errors(&runs());
+		let line = "I cant spel wel";
+		assert_eq!(indication(line, 0, &errors), Some("misspelled spel, wel".to_string()));
+		assert_eq!(indication("spel ", 7, &errors), Some("misspelled".to_string()));
+		assert_eq!(indication("cant ", 2, &errors), None);
+	}
+	#[test]
+	fn words_to_spell() {
+		let text: Vec<char> = "Hello  big world".chars().collect();
+		assert_eq!(word_at(&text, 0).as_deref(), Some("Hello"));
+		assert_eq!(word_at(&text, 8).as_deref(), Some("big"));
+		// just after a word
+		assert_eq!(word_at(&text, 5).as_deref(), Some("Hello"));
+		assert_eq!(word_at(&text, 16).as_deref(), Some("world"));
+		assert_eq!(word_at(&text, 6), None);
+	}
+	#[test]
+	fn code_points() {
+		assert_eq!(code_point('é'), "é, U+00E9");
+		assert_eq!(code_point(' '), "space, U+0020");
+		assert_eq!(code_point('😀'), "😀, U+1F600");
+	}
+}
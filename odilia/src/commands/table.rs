@@ -0,0 +1,315 @@
+use crate::state::ScreenReaderState;
+use atspi_client::convertable::Convertable;
+use atspi_common::{Role, ScrollType};
+use odilia_cache::{AccessiblePrimitive, CacheItem, TableHeader, TableInfo};
+use odilia_common::{
+	errors::{CacheError, OdiliaError},
+	events::TableAction,
+	result::OdiliaResult,
+};
+use ssip_client_async::Priority;
+
+/// Roles which contain cells.
+const TABLE_ROLES: [Role; 2] = [Role::Table, Role::TreeTable];
+/// Roles which can be a cell within a table.
+const CELL_ROLES: [Role; 5] = [
+	Role::TableCell,
+	Role::ColumnHeader,
+	Role::RowHeader,
+	Role::TableColumnHeader,
+	Role::TableRowHeader,
+];
+
+/// A (row, column) position within a table; both are zero-indexed.
+pub type CellPosition = (i32, i32);
+
+/// Calculate where a movement would take the user from `current`.
+/// Returns `None` if the movement would go past the edge of the table, or if `action` does not move at all.
+pub fn next_position(
+	action: TableAction,
+	current: CellPosition,
+	info: &TableInfo,
+) -> Option<CellPosition> {
+	let (row, column) = current;
+	let (last_row, last_column) = (info.rows - 1, info.columns - 1);
+	let next = match action {
+		TableAction::NextCell if column < last_column => (row, column + 1),
+		TableAction::NextCell => (row + 1, 0),
+		TableAction::PreviousCell if column > 0 => (row, column - 1),
+		TableAction::PreviousCell => (row - 1, last_column),
+		TableAction::NextRow => (row + 1, column),
+		TableAction::PreviousRow => (row - 1, column),
+		TableAction::NextColumn => (row, column + 1),
+		TableAction::PreviousColumn => (row, column - 1),
+		TableAction::FirstCell => (0, 0),
+		TableAction::LastCell => (last_row, last_column),
+		TableAction::ReadRow | TableAction::ReadColumn => return None,
+	};
+	let in_bounds = (0..=last_row).contains(&next.0) && (0..=last_column).contains(&next.1);
+	in_bounds.then_some(next)
+}
+
+/// Which headers should be read after moving from `previous` to `current`, as the row and column they belong to.
+/// The row header is only read when the row has changed, and likewise for the column header.
+pub fn changed_headers(
+	previous: CellPosition,
+	current: CellPosition,
+) -> (Option<i32>, Option<i32>) {
+	(
+		(previous.0 != current.0).then_some(current.0),
+		(previous.1 != current.1).then_some(current.1),
+	)
+}
+
+/// Build what should be spoken on arriving at a cell: the headers which are to be read (see [`changed_headers`]), then the text of the cell.
+pub fn cell_announcement(
+	row_header: Option<String>,
+	column_header: Option<String>,
+	cell_text: &str,
+) -> String {
+	let mut parts: Vec<String> = [row_header, column_header]
+		.into_iter()
+		.flatten()
+		.filter(|header| !header.is_empty())
+		.collect();
+	parts.push(if cell_text.trim().is_empty() {
+		"blank".to_string()
+	} else {
+		cell_text.to_string()
+	});
+	parts.join(", ")
+}
+
+/// Look up the name of a header of `table`, if there is one to read.
+/// Headers which can not be looked up are left out, since the cell itself can still be read.
+async fn header_name(
+	state: &ScreenReaderState,
+	table: &CacheItem,
+	header: TableHeader,
+	index: Option<i32>,
+) -> Option<String> {
+	match state
+		.cache
+		.get_or_create_table_header(&table.object, header, index?)
+		.await
+	{
+		Ok(name) => Some(name),
+		Err(e) => {
+			tracing::debug!(error = %e, "Could not get the header of a table");
+			None
+		}
+	}
+}
+
+/// Find the cell the user is in, and the table which contains it.
+async fn current_cell(state: &ScreenReaderState) -> OdiliaResult<(CacheItem, CacheItem)> {
+	let current = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let current = state.cache.get(&current).ok_or(CacheError::NoItem)?;
+	let ancestors = state.cache.get_ancestors(&current.object);
+	let cell = if CELL_ROLES.contains(&current.role) {
+		current
+	} else {
+		ancestors
+			.iter()
+			.find(|ancestor| CELL_ROLES.contains(&ancestor.role))
+			.cloned()
+			.ok_or_else(|| OdiliaError::Generic("Not in a table".to_string()))?
+	};
+	let table = ancestors
+		.into_iter()
+		.find(|ancestor| TABLE_ROLES.contains(&ancestor.role))
+		.ok_or_else(|| OdiliaError::Generic("Not in a table".to_string()))?;
+	Ok((table, cell))
+}
+
+/// Get the position of a cell, preferring the `TableCell` interface of the cell, and falling back to asking the table.
+async fn cell_position(
+	state: &ScreenReaderState,
+	table: &CacheItem,
+	cell: &CacheItem,
+) -> OdiliaResult<CellPosition> {
+	let cell_proxy = cell.object.clone().into_accessible(state.connection()).await?;
+	if let Ok(table_cell) = cell_proxy.to_table_cell().await {
+		if let Ok(position) = table_cell.position().await {
+			return Ok(position);
+		}
+	}
+	// the table can only find a cell by its index among the children of the table itself
+	if cell.parent.key != table.object {
+		return Err(OdiliaError::Generic(
+			"Could not find the position of the cell".to_string(),
+		));
+	}
+	let index = cell_proxy.get_index_in_parent().await?;
+	let table_proxy = table
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_table()
+		.await?;
+	let position = tokio::try_join!(
+		table_proxy.get_row_at_index(index),
+		table_proxy.get_column_at_index(index),
+	)?;
+	if position.0 < 0 || position.1 < 0 {
+		return Err(OdiliaError::Generic(
+			"Could not find the position of the cell".to_string(),
+		));
+	}
+	Ok(position)
+}
+
+async fn cell_at(
+	state: &ScreenReaderState,
+	table: &CacheItem,
+	position: CellPosition,
+) -> OdiliaResult<CacheItem> {
+	let table_proxy = table
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_table()
+		.await?;
+	let cell: AccessiblePrimitive =
+		table_proxy.get_accessible_at(position.0, position.1).await?.into();
+	state.get_or_create_cache_item(cell).await
+}
+
+async fn move_to(state: &ScreenReaderState, cell: &CacheItem) -> OdiliaResult<()> {
+	let component = cell
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_component()
+		.await?;
+	// cells are not always focusable; the history is still updated so that the next command starts from this cell.
+	let _: bool = component.grab_focus().await.unwrap_or(false);
+	let _: bool = component.scroll_to(ScrollType::TopLeft).await?;
+	state.update_accessible(cell.object.clone()).await;
+	Ok(())
+}
+
+async fn read_line(
+	state: &ScreenReaderState,
+	table: &CacheItem,
+	positions: impl Iterator<Item = CellPosition>,
+) -> OdiliaResult<String> {
+	let mut texts = Vec::new();
+	for position in positions {
+		let cell = cell_at(state, table, position).await?;
+		texts.push(if cell.text.trim().is_empty() {
+			"blank".to_string()
+		} else {
+			cell.text
+		});
+	}
+	Ok(texts.join(", "))
+}
+
+/// Move around, or read part of, the table containing the current item.
+/// # Errors
+/// Fails if the current item is not inside a table, or if the table does not implement the `Table` interface.
+pub async fn navigate(state: &ScreenReaderState, action: TableAction) -> OdiliaResult<()> {
+	let (table, cell) = match current_cell(state).await {
+		Ok(found) => found,
+		Err(e) => {
+			state.say(Priority::Text, "Not in a table".to_string()).await;
+			return Err(e);
+		}
+	};
+	let info = state.cache.get_or_create_table(&table.object).await?;
+	let current = cell_position(state, &table, &cell).await?;
+	let text = match action {
+		TableAction::ReadRow => {
+			read_line(
+				state,
+				&table,
+				(0..info.columns).map(|column| (current.0, column)),
+			)
+			.await?
+		}
+		TableAction::ReadColumn => {
+			read_line(state, &table, (0..info.rows).map(|row| (row, current.1))).await?
+		}
+		movement => {
+			if let Some(next) = next_position(movement, current, &info) {
+				let next_cell = cell_at(state, &table, next).await?;
+				move_to(state, &next_cell).await?;
+				let (row, column) = changed_headers(current, next);
+				cell_announcement(
+					header_name(state, &table, TableHeader::Row, row).await,
+					header_name(state, &table, TableHeader::Column, column)
+						.await,
+					&next_cell.text,
+				)
+			} else {
+				"Edge of table".to_string()
+			}
+		}
+	};
+	state.say(Priority::Text, text).await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{cell_announcement, changed_headers, next_position};
+	use odilia_cache::TableInfo;
+	use odilia_common::events::TableAction;
+
+	fn info() -> TableInfo {
+		TableInfo { rows: 3, columns: 2, ..TableInfo::default() }
+	}
+
+	#[test]
+	fn next_cell_wraps_to_next_row() {
+		assert_eq!(next_position(TableAction::NextCell, (0, 0), &info()), Some((0, 1)));
+		assert_eq!(next_position(TableAction::NextCell, (0, 1), &info()), Some((1, 0)));
+		assert_eq!(next_position(TableAction::NextCell, (2, 1), &info()), None);
+	}
+	#[test]
+	fn previous_cell_wraps_to_previous_row() {
+		assert_eq!(next_position(TableAction::PreviousCell, (1, 0), &info()), Some((0, 1)));
+		assert_eq!(next_position(TableAction::PreviousCell, (0, 0), &info()), None);
+	}
+	#[test]
+	fn rows_and_columns_stop_at_edges() {
+		assert_eq!(next_position(TableAction::NextRow, (1, 1), &info()), Some((2, 1)));
+		assert_eq!(next_position(TableAction::NextRow, (2, 1), &info()), None);
+		assert_eq!(next_position(TableAction::PreviousColumn, (1, 0), &info()), None);
+		assert_eq!(next_position(TableAction::NextColumn, (1, 1), &info()), None);
+	}
+	#[test]
+	fn first_and_last_cell() {
+		assert_eq!(next_position(TableAction::FirstCell, (2, 1), &info()), Some((0, 0)));
+		assert_eq!(next_position(TableAction::LastCell, (0, 0), &info()), Some((2, 1)));
+	}
+	#[test]
+	fn headers_read_only_on_change() {
+		assert_eq!(changed_headers((1, 0), (1, 1)), (None, Some(1)));
+		assert_eq!(changed_headers((1, 1), (2, 1)), (Some(2), None));
+		assert_eq!(changed_headers((0, 1), (1, 0)), (Some(1), Some(0)));
+	}
+	#[test]
+	fn announcing_cells() {
+		assert_eq!(
+			cell_announcement(None, Some("Price".to_string()), "3.00"),
+			"Price, 3.00"
+		);
+		assert_eq!(
+			cell_announcement(
+				Some("Apples".to_string()),
+				Some("Name".to_string()),
+				"Apples"
+			),
+			"Apples, Name, Apples"
+		);
+	}
+	#[test]
+	fn empty_headers_and_cells() {
+		assert_eq!(cell_announcement(Some(String::new()), None, ""), "blank");
+	}
+}
@@ -0,0 +1,293 @@
+use crate::{commands::review::window_of, state::ScreenReaderState};
+use atspi_common::{Interface, RelationType, Role, State, StateSet};
+use atspi_proxies::{accessible::Accessible, text::Text};
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::{errors::CacheError, result::OdiliaResult};
+use ssip_client_async::Priority;
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+/// If Where Am I is requested again within this time, the detailed version is given.
+const DOUBLE_PRESS: Duration = Duration::from_millis(500);
+
+/// Roles whose position within their group ("3 of 7") is worth announcing when they are focused.
+pub const GROUPED_ROLES: [Role; 7] = [
+	Role::ListItem,
+	Role::MenuItem,
+	Role::CheckMenuItem,
+	Role::RadioMenuItem,
+	Role::PageTab,
+	Role::RadioButton,
+	Role::TreeItem,
+];
+
+/// Read the position of an item within its group from the `posinset` and `setsize` attributes, which web content (and some toolkits) provide.
+pub fn position_from_attributes(attributes: &HashMap<String, String>) -> Option<(i32, i32)> {
+	let position = attributes.get("posinset")?.parse().ok()?;
+	let size = attributes.get("setsize")?.parse().ok()?;
+	(position > 0 && size >= position).then_some((position, size))
+}
+
+/// Work out the position of the sibling at `index` from the roles of all siblings.
+/// Only siblings with the same role are counted, so that separators and the like do not throw the count off.
+pub fn position_among(roles: &[Role], index: usize) -> Option<(i32, i32)> {
+	let role = roles.get(index)?;
+	let position = roles[..=index].iter().filter(|r| *r == role).count();
+	let size = roles.iter().filter(|r| *r == role).count();
+	Some((i32::try_from(position).ok()?, i32::try_from(size).ok()?))
+}
+
+/// The level of a tree item: from its `level` attribute if it has one, otherwise by counting the tree items it is nested in.
+pub fn tree_level(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	attributes: &HashMap<String, String>,
+) -> i32 {
+	if let Some(level) = attributes.get("level").and_then(|level| level.parse().ok()) {
+		return level;
+	}
+	let nested = state
+		.cache
+		.get_ancestors(&item.object)
+		.iter()
+		.filter(|ancestor| ancestor.role == Role::TreeItem)
+		.count();
+	i32::try_from(nested).unwrap_or(i32::MAX - 1) + 1
+}
+
+/// Find the position of `item` within its group, preferring the attributes the application provides, then the roles of its cached siblings, and finally its raw index within its parent.
+pub async fn position_in_group(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	attributes: &HashMap<String, String>,
+) -> Option<(i32, i32)> {
+	if let Some(position) = position_from_attributes(attributes) {
+		return Some(position);
+	}
+	if let Some(parent) = state.cache.get(&item.parent.key) {
+		let siblings: Option<Vec<CacheItem>> = parent
+			.children
+			.iter()
+			.map(|child| state.cache.get(&child.key))
+			.collect();
+		if let Some(siblings) = siblings {
+			let roles: Vec<Role> =
+				siblings.iter().map(|sibling| sibling.role).collect();
+			if let Some(index) =
+				siblings.iter().position(|sibling| sibling.object == item.object)
+			{
+				return position_among(&roles, index);
+			}
+		}
+	}
+	let parent = state.get_or_create_cache_item(item.parent.key.clone()).await.ok()?;
+	let position = item.index.checked_add(1).filter(|position| *position > 0)?;
+	(parent.children_num > 0).then_some((position, parent.children_num))
+}
+
+/// The states worth mentioning when describing an object, in the order they are spoken.
+pub fn spoken_states(states: StateSet) -> Vec<&'static str> {
+	let mut spoken = Vec::new();
+	if states.contains(State::Checked) {
+		spoken.push("checked");
+	} else if states.contains(State::Indeterminate) {
+		spoken.push("partially checked");
+	} else if states.contains(State::Checkable) {
+		spoken.push("not checked");
+	}
+	if states.contains(State::Pressed) {
+		spoken.push("pressed");
+	}
+	if states.contains(State::Expanded) {
+		spoken.push("expanded");
+	} else if states.contains(State::Expandable) {
+		spoken.push("collapsed");
+	}
+	if states.contains(State::Selected) {
+		spoken.push("selected");
+	}
+	if states.contains(State::Required) {
+		spoken.push("required");
+	}
+	if states.contains(State::InvalidEntry) {
+		spoken.push("invalid entry");
+	}
+	if states.contains(State::ReadOnly) {
+		spoken.push("read only");
+	}
+	if states.contains(State::Focusable) && !states.contains(State::Enabled) {
+		spoken.push("unavailable");
+	}
+	spoken
+}
+
+/// Find the (one-indexed) line and column of `offset` within `text`.
+/// `offset` is in characters, as AT-SPI offsets are.
+pub fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+	let mut line = 1;
+	let mut column = 1;
+	for c in text.chars().take(offset) {
+		if c == '\n' {
+			line += 1;
+			column = 1;
+		} else {
+			column += 1;
+		}
+	}
+	(line, column)
+}
+
+/// How a relation is introduced in the detailed description, or `None` if it is not worth mentioning.
+pub fn relation_phrase(relation: RelationType) -> Option<&'static str> {
+	Some(match relation {
+		RelationType::LabelledBy => "labelled by",
+		RelationType::DescribedBy => "described by",
+		RelationType::ControllerFor => "controls",
+		RelationType::ControlledBy => "controlled by",
+		RelationType::MemberOf => "member of",
+		RelationType::FlowsTo => "flows to",
+		RelationType::FlowsFrom => "flows from",
+		RelationType::Details => "details in",
+		RelationType::ErrorMessage => "error message",
+		_ => return None,
+	})
+}
+
+async fn label_for(item: &CacheItem) -> OdiliaResult<String> {
+	if item.text.trim().is_empty() {
+		item.name().await
+	} else {
+		Ok(item.text.trim().to_string())
+	}
+}
+
+async fn caret_position(item: &CacheItem) -> Option<String> {
+	if !item.interfaces.contains(Interface::Text) {
+		return None;
+	}
+	let offset = usize::try_from(item.caret_offset().await.ok()?).ok()?;
+	let (line, column) = line_and_column(&item.text, offset);
+	Some(format!("line {line}, column {column}"))
+}
+
+async fn relations(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<Vec<String>> {
+	let accessible = item.object.clone().into_accessible(state.connection()).await?;
+	let mut spoken = Vec::new();
+	for (relation, targets) in accessible.get_relation_set().await? {
+		let Some(phrase) = relation_phrase(relation) else {
+			continue;
+		};
+		let mut names = Vec::new();
+		for target in targets {
+			let target: AccessiblePrimitive = target.into();
+			names.push(label_for(&state.get_or_create_cache_item(target).await?).await?);
+		}
+		spoken.push(format!("{phrase} {}", names.join(", ")));
+	}
+	Ok(spoken)
+}
+
+async fn describe(
+	state: &ScreenReaderState,
+	item: CacheItem,
+	detailed: bool,
+) -> OdiliaResult<String> {
+	let mut parts = vec![label_for(&item).await?, item.get_localized_role_name().await?];
+	parts.extend(spoken_states(item.states).into_iter().map(str::to_string));
+	let attributes = Accessible::get_attributes(&item).await.unwrap_or_default();
+	if let Some((position, size)) = position_in_group(state, &item, &attributes).await {
+		parts.push(format!("{position} of {size}"));
+	}
+	let window = window_of(state, item.clone()).await;
+	if window.object != item.object {
+		parts.push(format!("in {}", label_for(&window).await?));
+	}
+	let application =
+		item.app.clone()
+			.into_accessible(state.connection())
+			.await?
+			.name()
+			.await?;
+	parts.push(application);
+	parts.extend(caret_position(&item).await);
+	if detailed {
+		let description = item.description().await?;
+		if !description.trim().is_empty() {
+			parts.push(description);
+		}
+		parts.extend(relations(state, &item).await?);
+	}
+	parts.retain(|part| !part.trim().is_empty());
+	Ok(parts.join(", "))
+}
+
+/// Describe the focused item: its name, role, states, position in its group, window, application, and caret position.
+/// When requested twice in quick succession, the description and relations are included as well.
+/// # Errors
+/// Fails if nothing is focused, or if any of the `DBus` calls needed to describe it fail.
+pub async fn speak(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let now = Instant::now();
+	let previous = state.last_where_am_i.lock().await.replace(now);
+	let detailed =
+		previous.map_or(false, |previous| now.duration_since(previous) < DOUBLE_PRESS);
+	let focused = state.history_item(0).await.ok_or(CacheError::NoItem)?;
+	let item = state.get_or_create_cache_item(focused).await?;
+	let description = describe(state, item, detailed).await?;
+	if detailed {
+		// do not read both versions back to back
+		state.stop_speech().await;
+	}
+	state.say(Priority::Text, description).await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{line_and_column, position_among, position_from_attributes, spoken_states};
+	use atspi_common::{Role, State, StateSet};
+	use std::collections::HashMap;
+
+	#[test]
+	fn states_in_spoken_order() {
+		let states = StateSet::new(State::Checkable | State::Expandable | State::Selected);
+		assert_eq!(spoken_states(states), vec!["not checked", "collapsed", "selected"]);
+		let states = StateSet::new(State::Checked | State::Expanded | State::Expandable);
+		assert_eq!(spoken_states(states), vec!["checked", "expanded"]);
+	}
+	#[test]
+	fn unavailable_only_when_focusable() {
+		assert_eq!(spoken_states(StateSet::new(State::Focusable)), vec!["unavailable"]);
+		assert!(spoken_states(StateSet::new(State::Showing)).is_empty());
+		assert!(spoken_states(StateSet::new(State::Focusable | State::Enabled)).is_empty());
+	}
+	#[test]
+	fn position_from_posinset_and_setsize() {
+		let attributes = HashMap::from([
+			("posinset".to_string(), "3".to_string()),
+			("setsize".to_string(), "7".to_string()),
+		]);
+		assert_eq!(position_from_attributes(&attributes), Some((3, 7)));
+		let invalid = HashMap::from([
+			("posinset".to_string(), "0".to_string()),
+			("setsize".to_string(), "7".to_string()),
+		]);
+		assert_eq!(position_from_attributes(&invalid), None);
+		assert_eq!(position_from_attributes(&HashMap::new()), None);
+	}
+	#[test]
+	fn position_skips_other_roles() {
+		let roles = [Role::MenuItem, Role::Separator, Role::MenuItem, Role::MenuItem];
+		assert_eq!(position_among(&roles, 2), Some((2, 3)));
+		assert_eq!(position_among(&roles, 1), Some((1, 1)));
+		assert_eq!(position_among(&roles, 4), None);
+	}
+	#[test]
+	fn line_and_column_from_offset() {
+		assert_eq!(line_and_column("hello", 0), (1, 1));
+		assert_eq!(line_and_column("hello\nworld", 5), (1, 6));
+		assert_eq!(line_and_column("hello\nworld", 8), (2, 3));
+		assert_eq!(line_and_column("a\n\nb", 3), (3, 1));
+	}
+}
@@ -0,0 +1,194 @@
+//! Deciding how much text to read when the caret moves.
+//! The key that moved the caret is the best guide, when the input handler has told us about it; otherwise the granularity is worked out from where the caret was and is within the cached text.
+//! Odilia does not see the keys passed through to applications itself: whatever handles the keyboard has to send a [`CaretKey`] over the input socket when it passes one of them through (see the `odilia-input` README).
+
+use crate::{commands::where_am_i::line_and_column, state::ScreenReaderState};
+use atspi_common::Granularity;
+use atspi_proxies::text::Text;
+use odilia_cache::CacheItem;
+use odilia_common::{events::CaretKey, result::OdiliaResult};
+use std::time::{Duration, Instant};
+
+/// A caret key only explains caret movements which arrive within this time of it.
+pub const KEY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The granularity to read for a movement caused by `key`:
+///
+/// | key                        | read            |
+/// |----------------------------|-----------------|
+/// | left/right arrow           | character       |
+/// | control with left/right    | word            |
+/// | up/down arrow              | line            |
+/// | control with up/down       | paragraph       |
+/// | home/end                   | character       |
+/// | control with home/end      | line            |
+/// | page up/down               | line            |
+pub fn from_key(key: CaretKey) -> Granularity {
+	match key {
+		CaretKey::Character | CaretKey::LineEdge => Granularity::Char,
+		CaretKey::Word => Granularity::Word,
+		CaretKey::Line | CaretKey::DocumentEdge | CaretKey::Page => Granularity::Line,
+		CaretKey::Paragraph => Granularity::Paragraph,
+	}
+}
+
+/// Whether `offset` is at the start or the end of a line of `text`.
+fn at_line_edge(text: &str, offset: usize) -> bool {
+	let mut chars = text.chars().skip(offset.saturating_sub(1));
+	let before = if offset == 0 { None } else { chars.next() };
+	let after = chars.next();
+	before.map_or(true, |c| c == '\n') || after.map_or(true, |c| c == '\n')
+}
+
+/// Guess the granularity of a caret movement within `text` from `old` to `new` (character offsets), when the key is not known:
+///
+/// | movement                               | read      |
+/// |----------------------------------------|-----------|
+/// | onto another line                      | line      |
+/// | by one character                       | character |
+/// | to the start or end of the same line   | character |
+/// | anything else                          | unknown   |
+///
+/// Returns `None` when the movement does not fit any of these, which is usually movement by word.
+pub fn from_text(text: &str, old: usize, new: usize) -> Option<Granularity> {
+	if old == new {
+		return None;
+	}
+	let (old_line, _) = line_and_column(text, old);
+	let (new_line, _) = line_and_column(text, new);
+	if old_line != new_line {
+		return Some(Granularity::Line);
+	}
+	if old.abs_diff(new) == 1 || at_line_edge(text, new) {
+		return Some(Granularity::Char);
+	}
+	None
+}
+
+/// The paragraph (the text between two line breaks) containing the character offset `offset`, and the offset it starts at.
+pub fn paragraph_at(text: &str, offset: usize) -> (String, usize) {
+	let mut start = 0;
+	for (line_start, paragraph) in text.split('\n').scan(0, |position, paragraph| {
+		let line_start = *position;
+		*position += paragraph.chars().count() + 1;
+		Some((line_start, paragraph))
+	}) {
+		start = line_start;
+		if offset <= line_start + paragraph.chars().count() {
+			return (paragraph.to_string(), line_start);
+		}
+	}
+	(text.chars().skip(start).collect(), start)
+}
+
+/// Remember the caret key the input handler passed through, for the caret movement it causes.
+pub async fn key_pressed(state: &ScreenReaderState, key: CaretKey) {
+	*state.last_caret_key.lock().await = Some((key, Instant::now()));
+}
+
+/// Take the caret key which was passed through most recently, unless it is too old to have caused the current movement.
+pub async fn take_key(state: &ScreenReaderState) -> Option<CaretKey> {
+	let (key, at) = state.last_caret_key.lock().await.take()?;
+	(at.elapsed() < KEY_TIMEOUT).then_some(key)
+}
+
+/// The text at the character offset `offset` of `text` with the given granularity, and the offset it starts at, if it can be found within the cached text.
+/// Only paragraphs (the text between two line breaks) can be: where text wraps onto the next line is only known to the application.
+pub fn from_cache(text: &str, offset: usize, granularity: Granularity) -> Option<(String, usize)> {
+	(granularity == Granularity::Paragraph).then(|| paragraph_at(text, offset))
+}
+
+/// Read the text of `item` at `offset` with the given granularity, along with the offset the text starts at.
+/// # Errors
+/// Fails if the text can not be fetched; anything but a paragraph needs a `DBus` call.
+pub async fn read(
+	item: &CacheItem,
+	offset: i32,
+	granularity: Granularity,
+) -> OdiliaResult<(String, i32)> {
+	if let Some((text, start)) = from_cache(&item.text, usize::try_from(offset)?, granularity) {
+		return Ok((text, i32::try_from(start)?));
+	}
+	let (text, start, _) = item.get_string_at_offset(offset, granularity).await?;
+	Ok((text, start))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{from_cache, from_key, from_text, paragraph_at};
+	use atspi_common::Granularity;
+	use odilia_common::events::{CaretKey, ScreenReaderEvent};
+
+	const TEXT: &str = "first line\nsecond line\n\nlast";
+
+	#[test]
+	fn granularity_from_keys() {
+		let table = [
+			(CaretKey::Character, Granularity::Char),
+			(CaretKey::Word, Granularity::Word),
+			(CaretKey::Line, Granularity::Line),
+			(CaretKey::Paragraph, Granularity::Paragraph),
+			(CaretKey::LineEdge, Granularity::Char),
+			(CaretKey::DocumentEdge, Granularity::Line),
+			(CaretKey::Page, Granularity::Line),
+		];
+		for (key, granularity) in table {
+			assert_eq!(from_key(key), granularity, "{key:?}");
+		}
+	}
+	#[test]
+	fn granularity_from_text() {
+		let table = [
+			// right and left arrow
+			(0, 1, Some(Granularity::Char)),
+			(5, 4, Some(Granularity::Char)),
+			// down and up arrow, including onto an empty line
+			(3, 14, Some(Granularity::Line)),
+			(14, 3, Some(Granularity::Line)),
+			(13, 23, Some(Granularity::Line)),
+			// right arrow over a line break
+			(10, 11, Some(Granularity::Line)),
+			// end and home
+			(2, 10, Some(Granularity::Char)),
+			(17, 11, Some(Granularity::Char)),
+			// control with right arrow
+			(0, 6, None),
+			// no movement
+			(4, 4, None),
+		];
+		for (old, new, granularity) in table {
+			assert_eq!(from_text(TEXT, old, new), granularity, "{old} to {new}");
+		}
+	}
+	#[test]
+	fn paragraphs() {
+		assert_eq!(paragraph_at(TEXT, 0), ("first line".to_string(), 0));
+		assert_eq!(paragraph_at(TEXT, 10), ("first line".to_string(), 0));
+		assert_eq!(paragraph_at(TEXT, 11), ("second line".to_string(), 11));
+		assert_eq!(paragraph_at(TEXT, 23), (String::new(), 23));
+		assert_eq!(paragraph_at(TEXT, 26), ("last".to_string(), 24));
+		assert_eq!(paragraph_at(TEXT, 100), ("last".to_string(), 24));
+	}
+	#[test]
+	fn caret_keys_from_the_socket() {
+		let event: ScreenReaderEvent =
+			serde_json::from_str(r#"{"event": "caretKey", "args": {"key": "word"}}"#)
+				.expect("the documented caret key event should parse");
+		assert!(matches!(event, ScreenReaderEvent::CaretKey(CaretKey::Word)));
+	}
+	#[test]
+	fn soft_wrapped_lines_are_not_paragraphs() {
+		// a paragraph long enough to wrap onto several lines on screen
+		let text = "A paragraph which wraps over several lines on screen.\nNext";
+		assert_eq!(
+			from_cache(text, 30, Granularity::Paragraph),
+			Some((
+				"A paragraph which wraps over several lines on screen.".to_string(),
+				0
+			))
+		);
+		// only the application knows where it wraps, so lines are not taken from the cache
+		assert_eq!(from_cache(text, 30, Granularity::Line), None);
+		assert_eq!(from_cache(text, 30, Granularity::Word), None);
+	}
+}
@@ -0,0 +1,209 @@
+//! Echo of typing in editable text: typed characters, finished words, and deleted text.
+
+use crate::{commands::spelling, speech::capitals, state::ScreenReaderState};
+use atspi_common::{Role, State};
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::settings::{EchoSettings, PasswordEcho};
+use ssip_client_async::Priority;
+
+/// A change to the text of an editable item.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Edit<'a> {
+	/// `text` was inserted at character offset `at`.
+	Insert { text: &'a str, at: usize },
+	/// `text` was deleted from character offset `at`.
+	Delete { text: &'a str, at: usize },
+}
+
+impl Edit<'_> {
+	/// Where the caret is after typing this edit, or `None` if it was not typed (e.g. it was pasted), in which case the caret movement after it is read as usual.
+	pub fn typed_caret(&self) -> Option<usize> {
+		match *self {
+			Edit::Insert { text, at } if text.chars().count() == 1 => Some(at + 1),
+			Edit::Insert { .. } => None,
+			Edit::Delete { at, .. } => Some(at),
+		}
+	}
+}
+
+/// The name of a character, for those which speech synthesizers do not read by themselves.
+pub fn character_name(c: char) -> String {
+	match c {
+		' ' => "space".to_string(),
+		'\n' => "new line".to_string(),
+		'\t' => "tab".to_string(),
+		c => c.to_string(),
+	}
+}
+
+/// Speak whitespace by name when it is all there is; otherwise the text as it is.
+fn spoken(text: &str) -> String {
+	let mut chars = text.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) => character_name(c),
+		_ => text.to_string(),
+	}
+}
+
+/// The word which ends at character offset `end` in `text`, if there is one.
+pub fn word_before(text: &str, end: usize) -> Option<String> {
+	let before: Vec<char> = text.chars().take(end).collect();
+	let start = before
+		.iter()
+		.rposition(|c| !c.is_alphanumeric())
+		.map_or(0, |idx| idx + 1);
+	let word: String = before[start..].iter().collect();
+	(!word.is_empty()).then_some(word)
+}
+
+/// Decide what to say for an edit, given the text after the edit and whether the item is a password field.
+/// Only typing (a single character at a time) is echoed; pasted text is left to the caret movement that follows it.
+pub fn echo(settings: &EchoSettings, edit: Edit, text: &str, password: bool) -> Vec<String> {
+	let mut spoken_text = Vec::new();
+	match edit {
+		Edit::Insert { text: inserted, at } => {
+			let mut chars = inserted.chars();
+			let (Some(typed), None) = (chars.next(), chars.next()) else {
+				return spoken_text;
+			};
+			if password {
+				if settings.passwords == PasswordEcho::Star && settings.characters {
+					spoken_text.push("star".to_string());
+				}
+				return spoken_text;
+			}
+			if settings.characters {
+				spoken_text.push(character_name(typed));
+			}
+			if settings.words && !typed.is_alphanumeric() {
+				spoken_text.extend(word_before(text, at));
+			}
+		}
+		Edit::Delete { text: deleted, .. } => {
+			if !settings.deletions || deleted.is_empty() {
+				return spoken_text;
+			}
+			if password {
+				if settings.passwords == PasswordEcho::Star {
+					spoken_text.push("star".to_string());
+				}
+				return spoken_text;
+			}
+			spoken_text.push(spoken(deleted));
+		}
+	}
+	spoken_text
+}
+
+/// Echo an edit made by the user to `item`, if it is the focused editable item.
+pub async fn edited(state: &ScreenReaderState, item: &CacheItem, edit: Edit<'_>) {
+	if !(item.states.contains(State::Focused) && item.states.contains(State::Editable)) {
+		return;
+	}
+	if let Some(caret) = edit.typed_caret().and_then(|caret| i32::try_from(caret).ok()) {
+		*state.last_edit.lock().await = Some((item.object.clone(), caret));
+	}
+	let password = item.role == Role::PasswordText;
+	for text in echo(state.config.echo(), edit, &item.text, password) {
+		capitals::say_character(state, Priority::Text, text).await;
+	}
+	// point out a finished word if it is misspelled
+	let Edit::Insert { text: inserted, at } = edit else {
+		return;
+	};
+	if password || !state.config.echo().words || inserted.chars().any(char::is_alphanumeric) {
+		return;
+	}
+	let Some(word) = word_before(&item.text, at) else {
+		return;
+	};
+	let Ok(start) = i32::try_from(at - word.chars().count()) else {
+		return;
+	};
+	spelling::indicate(state, item, &word, start).await;
+}
+
+/// Whether the caret moving to `offset` in `object` just follows the last edit typed there.
+/// The edit has already been echoed, so the caret movement is not read; either way, the edit is forgotten.
+pub async fn follows_edit(
+	state: &ScreenReaderState,
+	object: &AccessiblePrimitive,
+	offset: i32,
+) -> bool {
+	state.last_edit
+		.lock()
+		.await
+		.take()
+		.map_or(false, |(edited, caret)| edited == *object && caret == offset)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{echo, word_before, Edit};
+	use odilia_common::settings::{EchoSettings, PasswordEcho};
+
+	fn settings(characters: bool, words: bool) -> EchoSettings {
+		EchoSettings { characters, words, deletions: true, passwords: PasswordEcho::Star }
+	}
+
+	#[test]
+	fn finding_the_previous_word() {
+		assert_eq!(word_before("hello world", 5), Some("hello".to_string()));
+		assert_eq!(word_before("hello world", 11), Some("world".to_string()));
+		assert_eq!(word_before("hello ", 6), None);
+	}
+	#[test]
+	fn characters_and_words() {
+		let typed_space = Edit::Insert { text: " ", at: 5 };
+		assert_eq!(
+			echo(&settings(true, false), typed_space, "hello ", false),
+			vec!["space"]
+		);
+		assert_eq!(
+			echo(&settings(false, true), typed_space, "hello ", false),
+			vec!["hello"]
+		);
+		assert_eq!(
+			echo(
+				&settings(true, true),
+				Edit::Insert { text: "o", at: 4 },
+				"hello",
+				false
+			),
+			vec!["o"]
+		);
+	}
+	#[test]
+	fn pasted_text_is_not_echoed() {
+		let pasted = Edit::Insert { text: "hello", at: 0 };
+		assert!(echo(&settings(true, true), pasted, "hello", false).is_empty());
+	}
+	#[test]
+	fn passwords_are_not_echoed() {
+		let typed = Edit::Insert { text: "s", at: 0 };
+		assert_eq!(echo(&settings(true, true), typed, "s", true), vec!["star"]);
+		let silent =
+			EchoSettings { passwords: PasswordEcho::Silent, ..settings(true, true) };
+		assert!(echo(&silent, typed, "s", true).is_empty());
+		assert!(echo(&silent, Edit::Delete { text: "s", at: 0 }, "", true).is_empty());
+	}
+	#[test]
+	fn deletions() {
+		assert_eq!(
+			echo(&settings(true, false), Edit::Delete { text: "a", at: 0 }, "", false),
+			vec!["a"]
+		);
+		assert_eq!(
+			echo(&settings(true, false), Edit::Delete { text: " ", at: 0 }, "", false),
+			vec!["space"]
+		);
+		let quiet = EchoSettings { deletions: false, ..settings(true, false) };
+		assert!(echo(&quiet, Edit::Delete { text: "a", at: 0 }, "", false).is_empty());
+	}
+	#[test]
+	fn caret_after_typing() {
+		assert_eq!(Edit::Insert { text: "a", at: 3 }.typed_caret(), Some(4));
+		assert_eq!(Edit::Delete { text: "a", at: 3 }.typed_caret(), Some(3));
+		assert_eq!(Edit::Insert { text: "pasted", at: 3 }.typed_caret(), None);
+	}
+}
@@ -0,0 +1,234 @@
+//! Handling of ARIA live regions: parts of a page which announce their own changes.
+//! Browsers expose the `live`, `atomic`, `relevant` and `busy` properties as object attributes on the region itself, and as `container-*` attributes on everything inside it.
+
+use crate::{speech::embedded, state::ScreenReaderState};
+use atspi_common::State;
+use atspi_proxies::accessible::Accessible;
+use odilia_cache::{AccessiblePrimitive, CacheItem};
+use odilia_common::{result::OdiliaResult, types::AriaLive};
+use ssip_client_async::Priority;
+use std::collections::HashMap;
+
+/// The kind of change that happened within a live region.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Change {
+	/// An object was added to the region.
+	Addition,
+	/// An object, or some text, was removed from the region.
+	Removal,
+	/// Text was inserted into an object in the region.
+	Text,
+}
+
+/// Which kinds of changes a live region wants announced; see the `aria-relevant` property.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Relevant {
+	pub additions: bool,
+	pub removals: bool,
+	pub text: bool,
+}
+
+impl Default for Relevant {
+	/// "additions text", as per the ARIA specification.
+	fn default() -> Self {
+		Self { additions: true, removals: false, text: true }
+	}
+}
+
+impl Relevant {
+	/// Parse a space separated list of `additions`, `removals`, `text` and `all`.
+	pub fn parse(value: &str) -> Self {
+		let mut relevant = Self { additions: false, removals: false, text: false };
+		for token in value.split_whitespace() {
+			match token {
+				"additions" => relevant.additions = true,
+				"removals" => relevant.removals = true,
+				"text" => relevant.text = true,
+				"all" => {
+					relevant =
+						Self { additions: true, removals: true, text: true }
+				}
+				other => tracing::trace!(token = other, "Unknown relevant value"),
+			}
+		}
+		relevant
+	}
+	pub fn allows(self, change: Change) -> bool {
+		match change {
+			Change::Addition => self.additions,
+			Change::Removal => self.removals,
+			Change::Text => self.text,
+		}
+	}
+}
+
+/// The properties of the live region an object belongs to.
+#[derive(Debug, Eq, PartialEq)]
+pub struct LiveProperties {
+	pub live: AriaLive,
+	pub atomic: bool,
+	pub relevant: Relevant,
+	pub busy: bool,
+}
+
+/// Read the live region properties from an object's attributes, preferring its own properties over those inherited from its container.
+/// Returns `None` if the object is not in a live region.
+pub fn from_attributes(attributes: &HashMap<String, String>) -> Option<LiveProperties> {
+	let get = |name: &str| {
+		attributes
+			.get(name)
+			.or_else(|| attributes.get(&format!("container-{name}")))
+			.map(String::as_str)
+	};
+	let live: AriaLive = get("live")?.parse().ok()?;
+	Some(LiveProperties {
+		live,
+		atomic: get("atomic") == Some("true"),
+		relevant: get("relevant").map(Relevant::parse).unwrap_or_default(),
+		busy: get("busy") == Some("true"),
+	})
+}
+
+/// Polite messages are queued behind other speech; assertive ones interrupt it.
+pub fn priority(live: &AriaLive) -> Option<Priority> {
+	match live {
+		AriaLive::Assertive => Some(Priority::Important),
+		AriaLive::Polite => Some(Priority::Message),
+		AriaLive::Off | AriaLive::Other(_) => None,
+	}
+}
+
+/// Find the live region properties for `item`, along with the root of the region.
+/// Objects inside a region carry its properties as `container-*` attributes, so only those objects need their ancestors searched for the root.
+async fn find_region(item: &CacheItem) -> Option<(LiveProperties, CacheItem)> {
+	let attributes = Accessible::get_attributes(item).await.ok()?;
+	let mut inherited = from_attributes(&attributes)?;
+	if attributes.contains_key("live") {
+		inherited.busy |= item.states.contains(State::Busy);
+		return Some((inherited, item.clone()));
+	}
+	let cache = item.cache.upgrade()?;
+	for ancestor in cache.get_ancestors(&item.object) {
+		let ancestor_attributes =
+			Accessible::get_attributes(&ancestor).await.unwrap_or_default();
+		if !ancestor_attributes.contains_key("live") {
+			continue;
+		}
+		let mut properties = from_attributes(&ancestor_attributes)?;
+		properties.busy |= ancestor.states.contains(State::Busy);
+		return Some((properties, ancestor));
+	}
+	// the root of the region is not cached; do the best we can with what the item inherited
+	Some((inherited, item.clone()))
+}
+
+/// Announce a change to `item`, if it is inside a live region that wants that kind of change announced.
+/// `text` is what changed; for atomic regions the whole region is read instead.
+/// While the region is busy, announcements are held back until [`flush`] is called for it.
+/// # Errors
+/// Fails if the name of the region can not be fetched when it has no text of its own.
+pub async fn announce(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	change: Change,
+	text: String,
+) -> OdiliaResult<()> {
+	let Some((properties, root)) = find_region(item).await else {
+		tracing::trace!("Not in a live region; nothing to announce.");
+		return Ok(());
+	};
+	let Some(priority) = priority(&properties.live) else {
+		return Ok(());
+	};
+	if !properties.relevant.allows(change) {
+		return Ok(());
+	}
+	let region = root.object.clone();
+	let text = if properties.atomic && change != Change::Removal {
+		let root = state.cache.get(&root.object).unwrap_or(root);
+		if root.text.trim().is_empty() {
+			root.name().await?
+		} else {
+			embedded::expand_item(&root, &root.text, 0).await
+		}
+	} else {
+		text
+	};
+	if text.trim().is_empty() {
+		return Ok(());
+	}
+	if properties.busy {
+		state.live_pending
+			.lock()
+			.await
+			.entry(region)
+			.or_default()
+			.push((priority, text));
+		return Ok(());
+	}
+	state.say(priority, text).await;
+	Ok(())
+}
+
+/// Speak everything held back while `region` was busy.
+pub async fn flush(state: &ScreenReaderState, region: &AccessiblePrimitive) {
+	let Some(pending) = state.live_pending.lock().await.remove(region) else {
+		return;
+	};
+	for (priority, text) in pending {
+		state.say(priority, text).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{from_attributes, Change, Relevant};
+	use odilia_common::types::AriaLive;
+	use std::collections::HashMap;
+
+	fn attributes(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+		pairs.iter()
+			.map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn relevant_parsing() {
+		assert_eq!(Relevant::parse("additions text"), Relevant::default());
+		let all = Relevant::parse("all");
+		assert!(all.allows(Change::Addition)
+			&& all.allows(Change::Removal)
+			&& all.allows(Change::Text));
+		let removals = Relevant::parse("removals");
+		assert!(removals.allows(Change::Removal) && !removals.allows(Change::Text));
+	}
+	#[test]
+	fn properties_from_container_attributes() {
+		let properties = from_attributes(&attributes(&[
+			("container-live", "polite"),
+			("container-atomic", "true"),
+			("container-relevant", "removals"),
+		]))
+		.expect("a live region");
+		assert_eq!(properties.live, AriaLive::Polite);
+		assert!(properties.atomic);
+		assert!(properties.relevant.allows(Change::Removal));
+		assert!(!properties.busy);
+	}
+	#[test]
+	fn own_properties_win() {
+		let properties = from_attributes(&attributes(&[
+			("live", "assertive"),
+			("container-live", "polite"),
+			("busy", "true"),
+		]))
+		.expect("a live region");
+		assert_eq!(properties.live, AriaLive::Assertive);
+		assert_eq!(properties.relevant, Relevant::default());
+		assert!(properties.busy);
+	}
+	#[test]
+	fn not_a_live_region() {
+		assert_eq!(from_attributes(&attributes(&[("tag", "div")])), None);
+	}
+}
@@ -1,6 +1,10 @@
 mod cache;
+pub mod caret;
 mod document;
+pub mod echo;
+mod live_region;
 mod object;
+mod window;
 
 use std::{collections::HashMap, sync::Arc};
 
@@ -10,7 +14,14 @@ use tokio::sync::{
 	mpsc::{Receiver, Sender},
 };
 
-use crate::state::ScreenReaderState;
+use crate::{
+	commands::{
+		alerts, elements_list, formatting, indentation, object_navigation, progress,
+		review, selection, spelling, table, where_am_i,
+	},
+	speech::{self, template, verbosity},
+	state::ScreenReaderState,
+};
 use atspi_client::{accessible_ext::AccessibleExt, convertable::Convertable};
 use atspi_common::events::Event;
 use atspi_common::{InterfaceSet, MatchType, MatcherArgs, Role, ScrollType};
@@ -52,11 +63,10 @@ pub async fn structural_navigation(
 		comp.scroll_to(ScrollType::TopLeft).await?;
 		state.update_accessible(curr_prim).await;
 		let _: bool = texti.set_caret_offset(0).await?;
-		let role = next.get_role().await?;
-		let len = texti.character_count().await?;
-		let text = texti.get_text(0, len).await?;
+		let item = state.get_or_create_cache_item(next.try_into()?).await?;
+		let text = template::describe(state, &item, String::new()).await?;
 		// saying awaits until it is done talking; you may want to spawn a task
-		state.say(Priority::Text, format!("{text}, {role}")).await;
+		state.say(Priority::Text, text).await;
 		Ok(true)
 	} else {
 		state.say(Priority::Text, format!("No more {role}s")).await;
@@ -64,6 +74,7 @@ pub async fn structural_navigation(
 	}
 }
 
+#[allow(clippy::too_many_lines)]
 pub async fn sr_event(
 	state: Arc<ScreenReaderState>,
 	sr_events: &mut Receiver<ScreenReaderEvent>,
@@ -90,6 +101,108 @@ pub async fn sr_event(
 						let mut sr_mode = state.mode.lock().await;
 						*sr_mode = new_sr_mode;
 			    }
+			    Some(ScreenReaderEvent::ElementsList(element_type)) => {
+				if let Err(e) = elements_list::open(&state, element_type).await {
+				    tracing::debug!(error = %e, "Could not open the elements list.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ListNavigation(action)) => {
+				if let Err(e) = elements_list::navigate(&state, action).await {
+				    tracing::debug!(error = %e, "Could not navigate the elements list.");
+				}
+			    }
+			    Some(ScreenReaderEvent::TableNavigation(action)) => {
+				if let Err(e) = table::navigate(&state, action).await {
+				    tracing::debug!(error = %e, "Could not navigate the table.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ReviewMove(direction, unit)) => {
+				if let Err(e) = review::move_cursor(&state, &direction, unit).await {
+				    tracing::debug!(error = %e, "Could not move the review cursor.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ReviewCurrent(unit)) => {
+				if let Err(e) = review::read_current(&state, unit).await {
+				    tracing::debug!(error = %e, "Could not read at the review cursor.");
+				}
+			    }
+			    Some(ScreenReaderEvent::RouteReviewToFocus) => {
+				if let Err(e) = review::route_to_focus(&state).await {
+				    tracing::debug!(error = %e, "Could not move the review cursor to focus.");
+				}
+			    }
+			    Some(ScreenReaderEvent::RouteFocusToReview) => {
+				if let Err(e) = review::route_focus(&state).await {
+				    tracing::debug!(error = %e, "Could not move focus to the review cursor.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ObjectNavigation(action)) => {
+				if let Err(e) = object_navigation::navigate(&state, action).await {
+				    tracing::debug!(error = %e, "Could not move the navigator object.");
+				}
+			    }
+			    Some(ScreenReaderEvent::WhereAmI) => {
+				if let Err(e) = where_am_i::speak(&state).await {
+				    tracing::debug!(error = %e, "Could not describe the focused item.");
+				}
+			    }
+			    Some(ScreenReaderEvent::AlertHistory(direction)) => {
+				alerts::review(&state, &direction).await;
+			    }
+			    Some(ScreenReaderEvent::ReadProgress) => {
+				progress::read_latest(&state).await;
+			    }
+			    Some(ScreenReaderEvent::CaretKey(key)) => {
+				caret::key_pressed(&state, key).await;
+			    }
+			    Some(ScreenReaderEvent::Punctuation(level)) => {
+				speech::set_punctuation(&state, level).await;
+			    }
+			    Some(ScreenReaderEvent::ReloadPronunciation) => {
+				if let Err(e) = speech::reload_pronunciation(&state).await {
+				    tracing::debug!(error = %e, "Could not reload the pronunciation dictionaries.");
+				}
+			    }
+			    Some(ScreenReaderEvent::Verbosity(level)) => {
+				if let Err(e) = verbosity::set_level(&state, level).await {
+				    tracing::debug!(error = %e, "Could not save the verbosity level.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ReadSelection) => {
+				if let Err(e) = selection::read(&state).await {
+				    tracing::debug!(error = %e, "Could not read the selection.");
+				}
+			    }
+			    Some(ScreenReaderEvent::ReadFormatting) => {
+				if let Err(e) = formatting::read(&state).await {
+				    tracing::debug!(error = %e, "Could not read the formatting.");
+				}
+			    }
+			    Some(ScreenReaderEvent::SpellingError(direction)) => {
+				if let Err(e) = spelling::jump(&state, &direction).await {
+				    tracing::debug!(error = %e, "Could not move to a spelling error.");
+				}
+			    }
+			    Some(ScreenReaderEvent::SpellWord(cursor)) => {
+				if let Err(e) = spelling::spell_word(&state, cursor).await {
+				    tracing::debug!(error = %e, "Could not spell the word.");
+				}
+			    }
+			    Some(ScreenReaderEvent::PhoneticCharacter(cursor)) => {
+				if let Err(e) = spelling::phonetic(&state, cursor).await {
+				    tracing::debug!(error = %e, "Could not read the character phonetically.");
+				}
+			    }
+			    Some(ScreenReaderEvent::CharacterCodePoint(cursor)) => {
+				if let Err(e) = spelling::read_code_point(&state, cursor).await {
+				    tracing::debug!(error = %e, "Could not read the code point of the character.");
+				}
+			    }
+			    Some(ScreenReaderEvent::LineAndColumn) => {
+				if let Err(e) = indentation::read_line_and_column(&state).await {
+				    tracing::debug!(error = %e, "Could not read the line and column.");
+				}
+			    }
 			    _ => { continue; }
 			};
 			continue;
@@ -179,6 +292,9 @@ async fn dispatch(state: &ScreenReaderState, event: Event) -> eyre::Result<()> {
 			document::dispatch(state, document_event).await?;
 		}
 		Event::Cache(cache_event) => cache::dispatch(state, cache_event).await?,
+		Event::Window(window_event) => {
+			window::dispatch(state, window_event).await?;
+		}
 		other_event => {
 			tracing::debug!(
 				"Ignoring event with unknown interface: {:#?}",
@@ -186,8 +302,6 @@ async fn dispatch(state: &ScreenReaderState, event: Event) -> eyre::Result<()> {
 			);
 		}
 	}
-	//let accessible_id = state.new_accessible(&interface).await?.path().try_into()?;
-	//state.update_accessible(accessible_id).await;
 	state.event_history_update(event).await;
 	Ok(())
 }
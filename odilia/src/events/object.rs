@@ -1,4 +1,7 @@
-use crate::state::ScreenReaderState;
+use crate::{
+	commands::{alerts, progress, selection},
+	state::ScreenReaderState,
+};
 use atspi_common::events::object::ObjectEvents;
 
 pub async fn dispatch(state: &ScreenReaderState, event: &ObjectEvents) -> eyre::Result<()> {
@@ -13,9 +16,42 @@ pub async fn dispatch(state: &ScreenReaderState, event: &ObjectEvents) -> eyre::
 		ObjectEvents::TextChanged(text_changed_event) => {
 			text_changed::dispatch(state, text_changed_event).await?;
 		}
+		ObjectEvents::TextSelectionChanged(text_selection_changed_event) => {
+			let item = state
+				.get_or_create_event_object_to_cache(text_selection_changed_event)
+				.await?;
+			selection::selection_changed(state, item).await?;
+		}
+		ObjectEvents::TextAttributesChanged(text_attributes_changed_event) => {
+			text_changed::attributes_changed(state, text_attributes_changed_event)?;
+		}
 		ObjectEvents::ChildrenChanged(children_changed_event) => {
 			children_changed::dispatch(state, children_changed_event).await?;
 		}
+		ObjectEvents::PropertyChange(property_change_event)
+			if property_change_event.property == "accessible-value" =>
+		{
+			let item = state
+				.get_or_create_event_object_to_cache(property_change_event)
+				.await?;
+			progress::value_changed(state, item).await?;
+		}
+		ObjectEvents::Announcement(announcement_event) => {
+			alerts::alert(state, announcement_event.text.clone()).await;
+		}
+		ObjectEvents::ActiveDescendantChanged(active_descendant_changed_event) => {
+			active_descendant_changed::dispatch(state, active_descendant_changed_event)
+				.await?;
+		}
+		ObjectEvents::RowInserted(_)
+		| ObjectEvents::RowDeleted(_)
+		| ObjectEvents::RowReordered(_)
+		| ObjectEvents::ColumnInserted(_)
+		| ObjectEvents::ColumnDeleted(_)
+		| ObjectEvents::ColumnReordered(_)
+		| ObjectEvents::ModelChanged(_) => {
+			table_changed::dispatch(state, event)?;
+		}
 		other_member => {
 			tracing::debug!("Ignoring event with unknown member: {:#?}", other_member);
 		}
@@ -24,16 +60,26 @@ pub async fn dispatch(state: &ScreenReaderState, event: &ObjectEvents) -> eyre::
 }
 
 mod text_changed {
-	use crate::state::ScreenReaderState;
-	use atspi_common::events::object::TextChangedEvent;
-	use odilia_cache::CacheItem;
-	use odilia_common::{
-		errors::OdiliaError,
-		result::OdiliaResult,
-		types::{AriaAtomic, AriaLive},
+	use crate::{
+		events::{
+			echo::{self, Edit},
+			live_region::{self, Change},
+		},
+		speech::embedded::{self, EMBEDDED_OBJECT},
+		state::ScreenReaderState,
 	};
-	use ssip_client_async::Priority;
-	use std::collections::HashMap;
+	use atspi_common::events::object::{TextAttributesChangedEvent, TextChangedEvent};
+	use odilia_cache::{AccessiblePrimitive, CacheItem};
+
+	/// The cached attribute runs of an item are fetched again after its attributes change.
+	pub fn attributes_changed(
+		state: &ScreenReaderState,
+		event: &TextAttributesChangedEvent,
+	) -> eyre::Result<()> {
+		state.cache
+			.remove_attribute_runs(&AccessiblePrimitive::from_event(event)?)?;
+		Ok(())
+	}
 
 	#[inline]
 	pub fn update_string_insert(
@@ -98,36 +144,6 @@ mod text_changed {
 		new_text.into_iter().collect()
 	}
 
-	#[inline]
-	/// Get the live state of a set of attributes.
-	/// Although the function only currently tests one attributes, in the future it may be imporant to inspect many attributes, compare them, or do additional logic.
-	pub fn get_live_state(attributes: &HashMap<String, String>) -> OdiliaResult<AriaLive> {
-		match attributes.get("live") {
-			None => Err(OdiliaError::NoAttributeError("live".to_string())),
-			Some(live) => Ok(serde_plain::from_str(live)?),
-		}
-	}
-
-	#[inline]
-	/// if the aria-live attribute is set to "polite", then set the prioirty of the message to speak once all other messages are done
-	/// if the aria-live attribute is set to "assertive", thenset the priority of the message to speak immediately, stop all other messages, and do not interrupt that piece of speech
-	/// otherwise, do not continue
-	pub fn live_to_priority(live_str: &AriaLive) -> Priority {
-		match live_str {
-			AriaLive::Assertive => Priority::Important,
-			AriaLive::Polite => Priority::Notification,
-			_ => Priority::Message,
-		}
-	}
-
-	#[inline]
-	pub fn get_atomic_state(attributes: &HashMap<String, String>) -> OdiliaResult<AriaAtomic> {
-		match attributes.get("atomic") {
-			None => Err(OdiliaError::NoAttributeError("atomic".to_string())),
-			Some(atomic) => Ok(serde_plain::from_str(atomic)?),
-		}
-	}
-
 	pub fn get_string_within_bounds(
 		start_pos: usize,
 		update_length: usize,
@@ -175,25 +191,6 @@ mod text_changed {
 		Ok(())
 	}
 
-	pub async fn speak_insertion(
-		state: &ScreenReaderState,
-		event: &TextChangedEvent,
-		attributes: &HashMap<String, String>,
-		cache_text: &str,
-	) -> OdiliaResult<()> {
-		// note, you should update the text before this happens, since this could potentially end the function
-		let live = get_live_state(attributes)?;
-		let atomic = get_atomic_state(attributes)?;
-		// if the atomic state is true, then read out the entite piece of text
-		// if atomic state is false, then only read out the portion which has been added
-		// otherwise, do not continue through this function
-		let text_to_say =
-			if atomic { cache_text.to_string() } else { (&event.text).try_into()? };
-		let prioirty = live_to_priority(&live);
-		state.say(prioirty, text_to_say).await;
-		Ok(())
-	}
-
 	/// The `insert` boolean, if set to true, will update the text in the cache.
 	/// If it is set to false, the selection will be removed.
 	/// The [`TextChangedEvent::operation`] value will *NOT* be checked by this function.
@@ -202,19 +199,11 @@ mod text_changed {
 		event: &TextChangedEvent,
 		insert: bool,
 	) -> eyre::Result<()> {
-		let accessible = state.new_accessible(event).await?;
 		let cache_item = state.get_or_create_event_object_to_cache(event).await?;
 		let updated_text: String = (&event.text).try_into()?;
-		let current_text = cache_item.text;
+		let current_text = cache_item.text.clone();
 		let (start_pos, update_length) =
 			(usize::try_from(event.start_pos)?, usize::try_from(event.length)?);
-		// if this is an insert, figure out if we shuld announce anything, then speak it;
-		// only after should we try to update the cache
-		if insert {
-			let attributes = accessible.get_attributes().await?;
-			let _: OdiliaResult<()> =
-				speak_insertion(state, event, &attributes, &current_text).await;
-		}
 
 		let text_selection_from_cache: String = current_text
 			.char_indices()
@@ -240,13 +229,64 @@ mod text_changed {
 					.collect();
 			})?;
 		}
+		// the embedded objects and attribute runs after the change have moved
+		state.cache.remove_hyperlinks(&cache_item.object);
+		state.cache.remove_attribute_runs(&cache_item.object)?;
+		// announce after updating the cache, so that atomic regions are read with the new text
+		let change = if insert { Change::Text } else { Change::Removal };
+		let item = state.cache.get(&cache_item.object).unwrap_or(cache_item);
+		// changes made by the application itself are not typing, so they are not echoed
+		if !event.operation.ends_with("/system") {
+			let edit = if insert {
+				Edit::Insert { text: &updated_text, at: start_pos }
+			} else {
+				Edit::Delete { text: &updated_text, at: start_pos }
+			};
+			echo::edited(state, &item, edit).await;
+		}
+		let text = if insert {
+			embedded::expand_item(&item, &updated_text, event.start_pos).await
+		} else {
+			// removed objects are gone, so there is nothing to expand them to
+			updated_text.replace(EMBEDDED_OBJECT, "")
+		};
+		live_region::announce(state, &item, change, text).await?;
 		Ok(())
 	}
 }
 
-mod children_changed {
+mod table_changed {
 	use crate::state::ScreenReaderState;
+	use atspi_common::events::object::ObjectEvents;
+	use odilia_cache::AccessiblePrimitive;
+
+	/// Any change to the rows or columns of a table invalidates its cached structure; it is fetched again the next time it is navigated.
+	pub fn dispatch(state: &ScreenReaderState, event: &ObjectEvents) -> eyre::Result<()> {
+		let table = match event {
+			ObjectEvents::RowInserted(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::RowDeleted(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::RowReordered(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::ColumnInserted(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::ColumnDeleted(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::ColumnReordered(e) => AccessiblePrimitive::from_event(e)?,
+			ObjectEvents::ModelChanged(e) => AccessiblePrimitive::from_event(e)?,
+			_ => return Ok(()),
+		};
+		state.cache.remove_table(&table);
+		tracing::debug!("Invalidated cached table structure.");
+		Ok(())
+	}
+}
+
+mod children_changed {
+	use crate::{
+		commands::alerts::{self, ALERT_ROLES},
+		events::live_region::{self, Change},
+		speech::embedded::{self, EMBEDDED_OBJECT},
+		state::ScreenReaderState,
+	};
 	use atspi_common::events::object::ChildrenChangedEvent;
+	use atspi_proxies::accessible::Accessible;
 	use odilia_cache::{AccessiblePrimitive, CacheItem};
 	use odilia_common::{errors::OdiliaError, result::OdiliaResult};
 	use std::sync::Arc;
@@ -257,7 +297,7 @@ mod children_changed {
 	) -> eyre::Result<()> {
 		// Dispatch based on kind
 		match event.operation.as_str() {
-			"remove" | "remove/system" => remove(state, event)?,
+			"remove" | "remove/system" => remove(state, event).await?,
 			"add" | "add/system" => add(state, event).await?,
 			kind => tracing::debug!(kind, "Ignoring event with unknown kind"),
 		}
@@ -267,14 +307,28 @@ mod children_changed {
 		state: &ScreenReaderState,
 		event: &ChildrenChangedEvent,
 	) -> eyre::Result<()> {
+		state.cache
+			.remove_hyperlinks(&AccessiblePrimitive::from_event(event)?);
 		let accessible = get_child_primitive(event)?
 			.into_accessible(state.atspi.connection())
 			.await?;
-		let _: OdiliaResult<CacheItem> = state
+		let item: OdiliaResult<CacheItem> = state
 			.cache
 			.get_or_create(&accessible, Arc::downgrade(&Arc::clone(&state.cache)))
 			.await;
 		tracing::debug!("Add a single item to cache.");
+		if let Ok(item) = item {
+			if ALERT_ROLES.contains(&item.role) {
+				alerts::alert(state, alerts::alert_text(state, &item).await?).await;
+				return Ok(());
+			}
+			let text = if item.text.trim().is_empty() {
+				item.name().await?
+			} else {
+				embedded::expand_item(&item, &item.text, 0).await
+			};
+			live_region::announce(state, &item, Change::Addition, text).await?;
+		}
 		Ok(())
 	}
 	#[inline]
@@ -283,16 +337,33 @@ mod children_changed {
 	) -> Result<AccessiblePrimitive, OdiliaError> {
 		Ok(event.child.clone().try_into()?)
 	}
-	pub fn remove(state: &ScreenReaderState, event: &ChildrenChangedEvent) -> eyre::Result<()> {
+	pub async fn remove(
+		state: &ScreenReaderState,
+		event: &ChildrenChangedEvent,
+	) -> eyre::Result<()> {
 		let prim = get_child_primitive(event)?;
+		let removed = state.cache.get(&prim);
 		state.cache.remove(&prim);
+		state.cache
+			.remove_hyperlinks(&AccessiblePrimitive::from_event(event)?);
 		tracing::debug!("Remove a single item from cache.");
+		// the removed item is already gone from the application, so the live region is found through its parent
+		let parent = state.cache.get(&AccessiblePrimitive::from_event(event)?);
+		if let (Some(removed), Some(parent)) = (removed, parent) {
+			let text = removed.text.replace(EMBEDDED_OBJECT, "");
+			live_region::announce(state, &parent, Change::Removal, text).await?;
+		}
 		Ok(())
 	}
 }
 
 mod text_caret_moved {
-	use crate::state::ScreenReaderState;
+	use crate::{
+		commands::{formatting, indentation, spelling, where_am_i::line_and_column},
+		events::{caret, echo},
+		speech::{capitals, embedded},
+		state::ScreenReaderState,
+	};
 	use atspi_common::events::object::TextCaretMovedEvent;
 	use atspi_common::Granularity;
 	use atspi_proxies::text::Text;
@@ -310,7 +381,7 @@ mod text_caret_moved {
 		old_item: CacheItem,
 		new_position: i32,
 		old_position: i32,
-	) -> Result<String, OdiliaError> {
+	) -> Result<(String, i32), OdiliaError> {
 		let new_id = new_item.object.clone();
 		let old_id = old_item.object.clone();
 		// NOTE: the errors here should never happen. Unless the user is at a position which is larger than the unsigned native integer size on the machine *and also* smaller than i32::MAX. This seems extremely rare.
@@ -321,19 +392,19 @@ mod text_caret_moved {
 		debug!("{new_id:?},{old_id:?}");
 		debug!("{old_pos},{new_pos}");
 		if new_id != old_id {
-			return Ok(new_item
+			let (text, start, _) = new_item
 				.get_string_at_offset(new_position, Granularity::Line)
-				.await?
-				.0);
+				.await?;
+			return Ok((text, start));
 		}
 		let first_position = min(new_position, old_position);
 		let last_position = max(new_position, old_position);
 		// if there is one character between the old and new position
 		if new_pos.abs_diff(old_pos) == 1 {
-			return Ok(new_item
+			let (text, start, _) = new_item
 				.get_string_at_offset(first_position, Granularity::Char)
-				.await?
-				.0);
+				.await?;
+			return Ok((text, start));
 		}
 		let first_word = new_item
 			.get_string_at_offset(first_position, Granularity::Word)
@@ -346,16 +417,16 @@ mod text_caret_moved {
 			 // if the end position of the first word immediately peceeds the start of the second word
 			 first_word.2.abs_diff(last_word.1) == 1
 		{
-			return new_item.get_text(first_position, last_position).await;
+			let text = new_item.get_text(first_position, last_position).await?;
+			return Ok((text, first_position));
 		}
 		// if the user has somehow from the beginning to the end. Usually happens with Home, the End.
 		if first_position == 0 && usize::try_from(last_position)? == new_item.text.len() {
-			return Ok(new_item.text.clone());
+			return Ok((new_item.text.clone(), 0));
 		}
-		Ok(new_item
-			.get_string_at_offset(new_position, Granularity::Line)
-			.await?
-			.0)
+		let (text, start, _) =
+			new_item.get_string_at_offset(new_position, Granularity::Line).await?;
+		Ok((text, start))
 	}
 
 	/// this must be checked *before* writing an accessible to the hsitory.
@@ -389,7 +460,6 @@ mod text_caret_moved {
 		Ok(true)
 	}
 
-	// TODO: left/right vs. up/down, and use generated speech
 	pub async fn text_cursor_moved(
 		state: &ScreenReaderState,
 		event: &TextCaretMovedEvent,
@@ -400,7 +470,33 @@ mod text_caret_moved {
 		let new_item = state.get_or_create_event_object_to_cache(event).await?;
 
 		let new_prim = new_item.object.clone();
-		let text = match state.history_item(0).await {
+		// typing moves the caret past what was typed, which has been echoed already
+		if echo::follows_edit(state, &new_prim, event.position).await {
+			state.update_accessible(new_prim).await;
+			return Ok(());
+		}
+		let item = new_item.clone();
+		let (text, start) = match state.history_item(0).await {
+			// within the same item, read as much as the key (or failing that, the movement itself) calls for
+			Some(old_prim) if old_prim == new_prim => {
+				let old_pos = state.previous_caret_position.load(Ordering::Relaxed);
+				let new_pos = event.position;
+				let granularity = match caret::take_key(state).await {
+					Some(key) => Some(caret::from_key(key)),
+					None => caret::from_text(
+						&new_item.text,
+						usize::try_from(old_pos)?,
+						usize::try_from(new_pos)?,
+					),
+				};
+				if let Some(granularity) = granularity {
+					caret::read(&new_item, new_pos, granularity).await?
+				} else {
+					// fall back on guessing from the words around the old and new positions
+					let old_item = new_item.clone();
+					new_position(new_item, old_item, new_pos, old_pos).await?
+				}
+			}
 			Some(old_prim) => {
 				let old_pos = state.previous_caret_position.load(Ordering::Relaxed);
 				let old_item =
@@ -410,10 +506,46 @@ mod text_caret_moved {
 			}
 			None => {
 				// if no previous item exists, as in the screen reader has just loaded, then read out the whole item.
-				new_item.get_string_at_offset(0, Granularity::Paragraph).await?.0
+				let (text, start, _) = new_item
+					.get_string_at_offset(0, Granularity::Paragraph)
+					.await?;
+				(text, start)
 			}
 		};
-		state.say(Priority::Text, text).await;
+		if let Err(e) = formatting::caret_moved(state, &item, event.position).await {
+			tracing::debug!(error = %e, "Could not announce formatting changes.");
+		}
+		// the history has not been updated yet, so this is still the item the caret moved from
+		let moved_line = match state.history_item(0).await {
+			Some(old_prim) if old_prim == new_prim => {
+				let old_pos = usize::try_from(
+					state.previous_caret_position.load(Ordering::Relaxed),
+				)?;
+				let new_pos = usize::try_from(event.position)?;
+				line_and_column(&item.text, old_pos).0
+					!= line_and_column(&item.text, new_pos).0
+			}
+			Some(_) => true,
+			None => false,
+		};
+		// reading a single character is not reading by word or line
+		if text.chars().count() > 1 {
+			let pitch = if moved_line {
+				indentation::line_entered(
+					state,
+					&item,
+					usize::try_from(event.position)?,
+				)
+				.await
+			} else {
+				None
+			};
+			let spoken = embedded::expand_item(&item, &text, start).await;
+			indentation::say_line(state, spoken, pitch).await;
+			spelling::indicate(state, &item, &text, start).await;
+		} else {
+			capitals::say_character(state, Priority::Text, text).await;
+		}
 		state.update_accessible(new_prim).await;
 		Ok(())
 	}
@@ -429,11 +561,107 @@ mod text_caret_moved {
 	}
 } // end of text_caret_moved
 
+mod active_descendant_changed {
+	use crate::{events::object::state_changed::announce_focus, state::ScreenReaderState};
+	use atspi_common::{events::object::ActiveDescendantChangedEvent, State};
+	use odilia_cache::AccessiblePrimitive;
+
+	/// Cache the new active descendant and, if its container has focus, announce it as if it had been focused itself.
+	pub async fn dispatch(
+		state: &ScreenReaderState,
+		event: &ActiveDescendantChangedEvent,
+	) -> eyre::Result<()> {
+		let container = AccessiblePrimitive::from_event(event)?;
+		let child: AccessiblePrimitive = event.child.clone().try_into()?;
+		let descendant = state.get_or_create_cache_item(child).await?;
+		let container = state.get_or_create_cache_item(container).await?;
+		if !container.states.contains(State::Focused) {
+			tracing::trace!("Active descendant changed in a container without focus; not announcing it.");
+			return Ok(());
+		}
+		announce_focus(state, descendant).await
+	}
+}
+
 mod state_changed {
-	use crate::state::ScreenReaderState;
-	use atspi_common::{events::object::StateChangedEvent, State};
+	use crate::{
+		commands::where_am_i::{position_in_group, tree_level, GROUPED_ROLES},
+		events::live_region,
+		speech::template,
+		state::ScreenReaderState,
+	};
+	use atspi_common::{events::object::StateChangedEvent, Role, State, StateSet};
 	use atspi_proxies::accessible::Accessible;
-	use odilia_cache::AccessiblePrimitive;
+	use odilia_cache::{AccessiblePrimitive, CacheItem};
+	use odilia_common::settings::{StateScope, StateSettings, StateVerbosity};
+	use std::{
+		sync::atomic::Ordering,
+		time::{Duration, Instant},
+	};
+
+	/// Identical state changes closer together than this are treated as one burst, and only announced once.
+	const STATE_BURST: Duration = Duration::from_millis(150);
+
+	/// What to say when `changed` is set (or unset, if `enabled` is false), and how verbosely.
+	/// Returns `None` if the change is not announced at all.
+	pub fn state_phrase(
+		settings: &StateSettings,
+		changed: State,
+		enabled: bool,
+	) -> Option<(StateVerbosity, &'static str)> {
+		let (verbosity, phrase) = match (changed, enabled) {
+			(State::Checked, true) => (settings.checked, "checked"),
+			(State::Checked, false) => (settings.checked, "not checked"),
+			(State::Expanded, true) => (settings.expanded, "expanded"),
+			(State::Expanded, false) => (settings.expanded, "collapsed"),
+			(State::Selected, true) => (settings.selected, "selected"),
+			(State::Pressed, true) => (settings.pressed, "pressed"),
+			(State::Pressed, false) => (settings.pressed, "not pressed"),
+			(State::InvalidEntry, true) => (settings.invalid, "invalid entry"),
+			_ => return None,
+		};
+		(verbosity != StateVerbosity::Off).then_some((verbosity, phrase))
+	}
+
+	/// Extra details spoken when an item within a group (a list, menu, tree, etc.) is focused.
+	/// `level` is only given for tree items, and is only spoken when it differs from `previous_level`.
+	pub fn group_details(
+		states: StateSet,
+		position: Option<(i32, i32)>,
+		level: Option<i32>,
+		previous_level: i32,
+	) -> Vec<String> {
+		let mut details = Vec::new();
+		if level.is_some() && states.contains(State::Expandable) {
+			details.push(if states.contains(State::Expanded) {
+				"expanded".to_string()
+			} else {
+				"collapsed".to_string()
+			});
+		}
+		if let Some((position, size)) = position {
+			details.push(format!("{position} of {size}"));
+		}
+		if let Some(level) = level.filter(|level| *level != previous_level) {
+			details.push(format!("level {level}"));
+		}
+		details
+	}
+
+	/// Whether a change of `changed` on `a11y` at `now` repeats `last`, the last change announced, as part of a burst.
+	pub fn is_burst_repeat(
+		last: Option<&(AccessiblePrimitive, State, bool, Instant)>,
+		a11y: &AccessiblePrimitive,
+		changed: State,
+		enabled: bool,
+		now: Instant,
+	) -> bool {
+		last.map_or(false, |(last_a11y, last_state, last_enabled, at)| {
+			last_a11y == a11y
+				&& *last_state == changed && *last_enabled == enabled
+				&& now.duration_since(*at) < STATE_BURST
+		})
+	}
 
 	/// Update the state of an item in the cache using a `StateChanged` event and the `ScreenReaderState` as context.
 	/// This writes to the value in-place, and does not clone any values.
@@ -445,15 +673,59 @@ mod state_changed {
 	) -> eyre::Result<bool> {
 		if active {
 			Ok(state.cache.modify_item(a11y, |cache_item| {
-				cache_item.states.remove(state_changed);
+				cache_item.states.insert(state_changed);
 			})?)
 		} else {
 			Ok(state.cache.modify_item(a11y, |cache_item| {
-				cache_item.states.insert(state_changed);
+				cache_item.states.remove(state_changed);
 			})?)
 		}
 	}
 
+	/// Announce a change of state, subject to the user's settings.
+	/// `previous` is whether the cache had `changed` set before this event; if it already matches, the event is a repeat.
+	pub async fn state_change(
+		state: &ScreenReaderState,
+		a11y: &AccessiblePrimitive,
+		changed: State,
+		enabled: bool,
+		previous: Option<bool>,
+	) -> eyre::Result<()> {
+		let settings = state.config.states();
+		let Some((verbosity, phrase)) = state_phrase(settings, changed, enabled) else {
+			return Ok(());
+		};
+		if previous == Some(enabled) {
+			return Ok(());
+		}
+		if settings.scope == StateScope::Focused
+			&& state.history_item(0).await.as_ref() != Some(a11y)
+		{
+			return Ok(());
+		}
+		let now = Instant::now();
+		let mut last = state.last_state_change.lock().await;
+		if is_burst_repeat(last.as_ref(), a11y, changed, enabled, now) {
+			return Ok(());
+		}
+		*last = Some((a11y.clone(), changed, enabled, now));
+		drop(last);
+		let profile = state.verbosity.lock().await.profile();
+		let text = if verbosity == StateVerbosity::Full && profile.state_names {
+			let name =
+				state.get_or_create_cache_item(a11y.clone()).await?.name().await?;
+			if name.trim().is_empty() {
+				phrase.to_string()
+			} else {
+				format!("{name}, {phrase}")
+			}
+		} else {
+			phrase.to_string()
+		};
+		state.say(ssip_client_async::Priority::Text, text).await;
+		Ok(())
+	}
+
 	pub async fn dispatch(
 		state: &ScreenReaderState,
 		event: &StateChangedEvent,
@@ -468,6 +740,10 @@ mod state_changed {
 		let state_value = event.enabled == 1;
 		// update cache with state of item
 		let a11y_prim = AccessiblePrimitive::from_event(event)?;
+		let previous = state
+			.cache
+			.get(&a11y_prim)
+			.map(|item| item.states.contains(a11y_state));
 		match update_state(state, &a11y_prim, a11y_state, state_value) {
 			Ok(false) => tracing::error!("Updating of the state was not succesful! The item with id {:?} was not found in the cache.", a11y_prim.id),
 			Ok(true) => tracing::trace!("Updated the state of accessible with ID {:?}, and state {:?} to {state_value}.", a11y_prim.id, a11y_state),
@@ -478,11 +754,10 @@ mod state_changed {
 		// enabled can only be 1 or 0, but is not a boolean over dbus
 		match (state_type, event.enabled == 1) {
 			(State::Focused, true) => focused(state, event).await?,
-			(state, enabled) => tracing::debug!(
-				"Ignoring state_changed event with unknown kind: {:?}/{}",
-				state,
-				enabled
-			),
+			(State::Busy, false) => live_region::flush(state, &a11y_prim).await,
+			(changed, enabled) => {
+				state_change(state, &a11y_prim, changed, enabled, previous).await?;
+			}
 		}
 		Ok(())
 	}
@@ -492,31 +767,47 @@ mod state_changed {
 		event: &StateChangedEvent,
 	) -> eyre::Result<()> {
 		let accessible = state.get_or_create_event_object_to_cache(event).await?;
+		announce_focus(state, accessible).await
+	}
+
+	/// Make `accessible` the current item and speak it, unless it already is the current item.
+	/// This is used for anything which acts like a focus change, not just the `Focused` state.
+	pub async fn announce_focus(
+		state: &ScreenReaderState,
+		accessible: CacheItem,
+	) -> eyre::Result<()> {
 		if let Some(curr) = state.history_item(0).await {
 			if curr == accessible.object {
 				return Ok(());
 			}
 		}
 
-		let (name, description, role, relation) = tokio::try_join!(
-			accessible.name(),
-			accessible.description(),
-			accessible.get_localized_role_name(),
+		let (relation, attributes) = tokio::try_join!(
 			accessible.get_relation_set(),
+			accessible.get_attributes(),
 		)?;
+		let mut details = Vec::new();
+		if GROUPED_ROLES.contains(&accessible.role) {
+			let position = position_in_group(state, &accessible, &attributes).await;
+			let level = (accessible.role == Role::TreeItem)
+				.then(|| tree_level(state, &accessible, &attributes));
+			let previous_level = state
+				.previous_tree_level
+				.swap(level.unwrap_or(0), Ordering::Relaxed);
+			details = group_details(accessible.states, position, level, previous_level);
+		} else {
+			state.previous_tree_level.store(0, Ordering::Relaxed);
+		}
 		state.update_accessible(accessible.object.clone()).await;
 		tracing::debug!(
 			"Focus event received on: {:?} with role {}",
 			accessible.object.id,
-			role
+			accessible.role
 		);
 		tracing::debug!("Relations: {:?}", relation);
 
-		state.say(
-			ssip_client_async::Priority::Text,
-			format!("{name}, {role}. {description}"),
-		)
-		.await;
+		let text = template::describe(state, &accessible, details.join(", ")).await?;
+		state.say(ssip_client_async::Priority::Text, text).await;
 
 		state.update_accessible(accessible.object).await;
 		Ok(())
@@ -525,12 +816,19 @@ mod state_changed {
 
 #[cfg(test)]
 mod tests {
-	use crate::events::object::text_caret_moved::new_position;
+	use crate::events::object::{
+		state_changed::{group_details, is_burst_repeat, state_phrase},
+		text_caret_moved::new_position,
+	};
 	use atspi_common::{Interface, InterfaceSet, Role, State, StateSet};
 	use atspi_connection::AccessibilityConnection;
 	use lazy_static::lazy_static;
 	use odilia_cache::{AccessiblePrimitive, Cache, CacheItem};
-	use std::sync::Arc;
+	use odilia_common::settings::{StateScope, StateSettings, StateVerbosity};
+	use std::{
+		sync::Arc,
+		time::{Duration, Instant},
+	};
 	use tokio_test::block_on;
 
 	static A11Y_PARAGRAPH_STRING: &str = "The AT-SPI (Assistive Technology Service Provider Interface) enables users of Linux to use their computer without sighted assistance. It was originally developed at Sun Microsystems, before they were purchased by Oracle.";
@@ -567,6 +865,7 @@ mod tests {
 			),
 			text: A11Y_PARAGRAPH_STRING.to_string(),
 			children: Vec::new(),
+			attribute_runs: Vec::new(),
 			cache: Arc::downgrade(&CACHE_ARC),
 		};
 		static ref ANSWER_VALUES: [(CacheItem, CacheItem, u32, u32, &'static str); 9] = [
@@ -621,7 +920,8 @@ mod tests {
 					ANSWER_VALUES[$idx].2.try_into().unwrap(),
 					ANSWER_VALUES[$idx].3.try_into().unwrap(),
 				))
-				.unwrap(),
+				.unwrap()
+				.0,
 				ANSWER_VALUES[$idx].4.to_string(),
 			);
 		};
@@ -663,4 +963,66 @@ mod tests {
 	fn test_text_navigation_full_item_back_to_front() {
 		check_answer_values!(8);
 	}
+	#[test]
+	fn group_details_for_list_items() {
+		let states = StateSet::new(State::Focused | State::Selected);
+		assert_eq!(group_details(states, Some((3, 7)), None, 0), vec!["3 of 7"]);
+		assert!(group_details(states, None, None, 0).is_empty());
+	}
+	#[test]
+	fn state_phrases_follow_settings() {
+		let settings = StateSettings {
+			scope: StateScope::Focused,
+			checked: StateVerbosity::Brief,
+			expanded: StateVerbosity::Full,
+			selected: StateVerbosity::Off,
+			pressed: StateVerbosity::Brief,
+			invalid: StateVerbosity::Brief,
+		};
+		assert_eq!(
+			state_phrase(&settings, State::Checked, false),
+			Some((StateVerbosity::Brief, "not checked"))
+		);
+		assert_eq!(
+			state_phrase(&settings, State::Expanded, false),
+			Some((StateVerbosity::Full, "collapsed"))
+		);
+		assert_eq!(state_phrase(&settings, State::Selected, true), None);
+		assert_eq!(state_phrase(&settings, State::InvalidEntry, false), None);
+		assert_eq!(state_phrase(&settings, State::Showing, true), None);
+	}
+	#[test]
+	fn group_details_for_tree_items() {
+		let collapsed = StateSet::new(State::Expandable);
+		let expanded = StateSet::new(State::Expandable | State::Expanded);
+		assert_eq!(
+			group_details(collapsed, Some((1, 4)), Some(2), 1),
+			vec!["collapsed", "1 of 4", "level 2"]
+		);
+		assert_eq!(
+			group_details(expanded, Some((2, 4)), Some(2), 2),
+			vec!["expanded", "2 of 4"]
+		);
+		assert_eq!(group_details(StateSet::empty(), None, Some(1), 0), vec!["level 1"]);
+	}
+	#[test]
+	fn state_bursts_are_per_item() {
+		let first = AccessiblePrimitive {
+			id: "/org/a11y/atspi/accessible/1".to_string(),
+			sender: ":1.2".into(),
+		};
+		let second = AccessiblePrimitive {
+			id: "/org/a11y/atspi/accessible/2".to_string(),
+			sender: ":1.2".into(),
+		};
+		let at = Instant::now();
+		let last = (first.clone(), State::Checked, true, at);
+		let soon = at + Duration::from_millis(10);
+		assert!(is_burst_repeat(Some(&last), &first, State::Checked, true, soon));
+		assert!(!is_burst_repeat(Some(&last), &second, State::Checked, true, soon));
+		assert!(!is_burst_repeat(Some(&last), &first, State::Checked, false, soon));
+		let later = at + Duration::from_secs(1);
+		assert!(!is_burst_repeat(Some(&last), &first, State::Checked, true, later));
+		assert!(!is_burst_repeat(None, &first, State::Checked, true, soon));
+	}
 }
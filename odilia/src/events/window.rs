@@ -0,0 +1,193 @@
+use crate::{
+	commands::alerts::{self, ALERT_ROLES},
+	state::ScreenReaderState,
+};
+use atspi_common::events::{
+	window::{ActivateEvent, CreateEvent, DeactivateEvent, WindowEvents},
+	GenericEvent,
+};
+use atspi_proxies::{accessible::Accessible, cache::CacheProxy};
+use odilia_cache::{AccessiblePrimitive, Cache, CacheItem};
+use odilia_common::errors::OdiliaError;
+use ssip_client_async::Priority;
+use std::{collections::HashMap, sync::Arc};
+use zbus::names::UniqueName;
+
+pub async fn activate(state: &ScreenReaderState, event: &ActivateEvent) -> Result<(), OdiliaError> {
+	let window = state.get_or_create_event_object_to_cache(event).await?;
+	*state.current_window.lock().await = Some(window.object.clone());
+	let previous = state.last_application.lock().await.replace(window.app.clone());
+	// windows of the same application share a bus name
+	let app_changed = previous.map_or(true, |previous| previous.sender != window.app.sender);
+	let title = window.name().await?;
+	let announcement = if app_changed {
+		let app = window
+			.app
+			.clone()
+			.into_accessible(state.connection())
+			.await?
+			.name()
+			.await?;
+		*state.current_application.lock().await = Some(app.clone());
+		format!("{title}, {app}")
+	} else {
+		title
+	};
+	state.say(Priority::Text, announcement).await;
+	// switching back to a window which is already cached needs nothing more
+	if window.children_num > 0 && state.cache.get_descendants(&window.object).is_empty() {
+		tokio::spawn(add_items(
+			Arc::clone(&state.cache),
+			state.connection().clone(),
+			event.sender().to_owned(),
+			window.object,
+		));
+	}
+	Ok(())
+}
+
+async fn get_items(
+	connection: &zbus::Connection,
+	sender: UniqueName<'static>,
+) -> zbus::Result<Vec<atspi_common::CacheItem>> {
+	CacheProxy::builder(connection)
+		.destination(sender)?
+		.path("/org/a11y/atspi/cache")?
+		.build()
+		.await?
+		.get_items()
+		.await
+}
+
+/// The items within `items` which are in the subtree of `window`, leaving out the window itself.
+pub fn window_subtree(
+	items: Vec<atspi_common::CacheItem>,
+	window: &AccessiblePrimitive,
+) -> Vec<atspi_common::CacheItem> {
+	let mut children: HashMap<AccessiblePrimitive, Vec<atspi_common::CacheItem>> =
+		HashMap::new();
+	for item in items {
+		children.entry(item.parent.clone().into()).or_default().push(item);
+	}
+	let mut subtree = Vec::new();
+	let mut parents = vec![window.clone()];
+	while let Some(parent) = parents.pop() {
+		for child in children.remove(&parent).unwrap_or_default() {
+			parents.push(child.object.clone().into());
+			subtree.push(child);
+		}
+	}
+	subtree
+}
+
+/// Add the items within `window`, a newly activated window of the application on the bus `sender`, to the cache, so that moving around the window does not wait on each item.
+/// Items which can not be added are logged and skipped.
+async fn add_items(
+	cache: Arc<Cache>,
+	connection: zbus::Connection,
+	sender: UniqueName<'static>,
+	window: AccessiblePrimitive,
+) {
+	let items = match get_items(&connection, sender).await {
+		Ok(items) => items,
+		Err(e) => {
+			tracing::debug!(error = %e, "Could not get the items of a newly activated window");
+			return;
+		}
+	};
+	for item in window_subtree(items, &window) {
+		let object: AccessiblePrimitive = item.object.clone().into();
+		if cache.get(&object).is_some() {
+			continue;
+		}
+		let added =
+			CacheItem::from_atspi_cache_item(item, Arc::downgrade(&cache), &connection)
+				.await
+				.and_then(|item| cache.add(item));
+		if let Err(e) = added {
+			tracing::debug!(error = %e, "Could not add an item of a newly activated window to the cache");
+		}
+	}
+	tracing::debug!("Added the items of a newly activated window to the cache.");
+}
+
+pub async fn deactivate(
+	state: &ScreenReaderState,
+	event: &DeactivateEvent,
+) -> Result<(), OdiliaError> {
+	let window = AccessiblePrimitive::from_event(event)?;
+	let mut current = state.current_window.lock().await;
+	if current.as_ref() == Some(&window) {
+		*current = None;
+	}
+	Ok(())
+}
+
+/// Alert dialogs and notifications are often new windows which never receive focus.
+pub async fn create(state: &ScreenReaderState, event: &CreateEvent) -> Result<(), OdiliaError> {
+	let window = state.get_or_create_event_object_to_cache(event).await?;
+	if ALERT_ROLES.contains(&window.role) {
+		alerts::alert(state, alerts::alert_text(state, &window).await?).await;
+	}
+	Ok(())
+}
+
+pub async fn dispatch(state: &ScreenReaderState, event: &WindowEvents) -> eyre::Result<()> {
+	// Dispatch based on member
+	match event {
+		WindowEvents::Activate(activate_event) => {
+			activate(state, activate_event).await?;
+		}
+		WindowEvents::Create(create_event) => {
+			create(state, create_event).await?;
+		}
+		WindowEvents::Deactivate(deactivate_event) => {
+			deactivate(state, deactivate_event).await?;
+		}
+		other_member => {
+			tracing::debug!("Ignoring event with unknown member: {:#?}", other_member);
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::window_subtree;
+	use atspi_common::CacheItem;
+	use odilia_cache::AccessiblePrimitive;
+	use zbus::zvariant::OwnedObjectPath;
+
+	fn pair(id: &str) -> (String, OwnedObjectPath) {
+		(
+			":1.2".to_string(),
+			format!("/org/a11y/atspi/accessible/{id}")
+				.try_into()
+				.expect("a valid object path"),
+		)
+	}
+	fn item(id: &str, parent: &str) -> CacheItem {
+		CacheItem { object: pair(id), parent: pair(parent), ..CacheItem::default() }
+	}
+
+	#[test]
+	fn only_the_window_is_added() {
+		let items = vec![
+			item("button", "panel"),
+			item("window", "root"),
+			item("other", "root"),
+			item("panel", "window"),
+			item("label", "other"),
+		];
+		let window: AccessiblePrimitive = pair("window").into();
+		let mut subtree: Vec<String> = window_subtree(items, &window)
+			.into_iter()
+			.map(|item| item.object.1.to_string())
+			.collect();
+		subtree.sort();
+		assert_eq!(
+			subtree,
+			["/org/a11y/atspi/accessible/button", "/org/a11y/atspi/accessible/panel"]
+		);
+	}
+}
@@ -8,8 +8,10 @@
 )]
 #![allow(clippy::multiple_crate_versions)]
 
+mod commands;
 mod events;
 mod logging;
+mod speech;
 mod state;
 
 use std::{process::exit, sync::Arc};
@@ -26,7 +28,7 @@ use crate::state::ScreenReaderState;
 use odilia_input::sr_event_receiver;
 use ssip_client_async::Priority;
 
-use atspi_common::events::{document, object};
+use atspi_common::events::{document, object, window};
 
 async fn sigterm_signal_watcher(shutdown_tx: broadcast::Sender<i32>) -> eyre::Result<()> {
 	let mut c = signal(SignalKind::interrupt())?;
@@ -70,8 +72,23 @@ async fn main() -> eyre::Result<()> {
 		state.register_event::<object::StateChangedEvent>(),
 		state.register_event::<object::TextCaretMovedEvent>(),
 		state.register_event::<object::ChildrenChangedEvent>(),
+		state.register_event::<object::ActiveDescendantChangedEvent>(),
+		state.register_event::<object::AnnouncementEvent>(),
+		state.register_event::<object::PropertyChangeEvent>(),
 		state.register_event::<object::TextChangedEvent>(),
+		state.register_event::<object::TextSelectionChangedEvent>(),
+		state.register_event::<object::TextAttributesChangedEvent>(),
+		state.register_event::<object::RowInsertedEvent>(),
+		state.register_event::<object::RowDeletedEvent>(),
+		state.register_event::<object::RowReorderedEvent>(),
+		state.register_event::<object::ColumnInsertedEvent>(),
+		state.register_event::<object::ColumnDeletedEvent>(),
+		state.register_event::<object::ColumnReorderedEvent>(),
+		state.register_event::<object::ModelChangedEvent>(),
 		state.register_event::<document::LoadCompleteEvent>(),
+		state.register_event::<window::ActivateEvent>(),
+		state.register_event::<window::DeactivateEvent>(),
+		state.register_event::<window::CreateEvent>(),
 		state.add_cache_match_rule(),
 	)?;
 
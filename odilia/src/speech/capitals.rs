@@ -0,0 +1,96 @@
+//! Telling capital letters apart from lowercase ones, when characters are read on their own or a word is spelled.
+
+use crate::{events::echo::character_name, speech, state::ScreenReaderState};
+use odilia_common::settings::CapitalIndication;
+use ssip_client_async::{
+	tokio::Request as SSIPRequest, CapitalLettersRecognitionMode, ClientScope, Priority,
+};
+
+/// The capital letter `text` consists of, if it is a single capital letter.
+pub fn capital(text: &str) -> Option<char> {
+	let mut chars = text.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) if c.is_uppercase() => Some(c),
+		_ => None,
+	}
+}
+
+/// The letters of `word`, one at a time, with whitespace spoken by name.
+pub fn letters(word: &str) -> Vec<String> {
+	word.chars().map(character_name).collect()
+}
+
+async fn send(state: &ScreenReaderState, request: SSIPRequest) -> bool {
+	state.ssip.send(request).await.is_ok()
+}
+
+/// Say `text`, indicating it as the user has configured if it is a single capital letter.
+pub async fn say_character(state: &ScreenReaderState, priority: Priority, text: String) {
+	let Some(c) = capital(&text) else {
+		state.say(priority, text).await;
+		return;
+	};
+	let settings = state.config.capitals();
+	match settings.indication {
+		CapitalIndication::None => {
+			state.say(priority, text).await;
+		}
+		CapitalIndication::Prefix => {
+			state.say(priority, format!("cap {c}")).await;
+		}
+		CapitalIndication::Pitch => {
+			speech::say_at_pitch(state, priority, text, settings.pitch).await;
+		}
+		CapitalIndication::Icon => {
+			// speech dispatcher only plays the icon for characters, and it would otherwise play it within words as well
+			let icon = SSIPRequest::SetCapitalLettersRecognitionMode(
+				ClientScope::Current,
+				CapitalLettersRecognitionMode::Icon,
+			);
+			if !(send(state, SSIPRequest::SetPriority(priority)).await
+				&& send(state, icon).await)
+			{
+				return;
+			}
+			send(state, SSIPRequest::SpeakChar(c)).await;
+			send(
+				state,
+				SSIPRequest::SetCapitalLettersRecognitionMode(
+					ClientScope::Current,
+					CapitalLettersRecognitionMode::None,
+				),
+			)
+			.await;
+		}
+	}
+}
+
+/// Spell `word` letter by letter, indicating capitals if the user wants them indicated when spelling.
+pub async fn spell(state: &ScreenReaderState, word: &str) {
+	for letter in letters(word) {
+		if state.config.capitals().spelling {
+			say_character(state, Priority::Text, letter).await;
+		} else {
+			state.say(Priority::Text, letter).await;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{capital, letters};
+
+	#[test]
+	fn capital_letters() {
+		assert_eq!(capital("A"), Some('A'));
+		assert_eq!(capital("É"), Some('É'));
+		assert_eq!(capital("a"), None);
+		assert_eq!(capital("1"), None);
+		assert_eq!(capital("AB"), None);
+		assert_eq!(capital(""), None);
+	}
+	#[test]
+	fn spelling() {
+		assert_eq!(letters("Hi there"), ["H", "i", "space", "t", "h", "e", "r", "e"]);
+	}
+}
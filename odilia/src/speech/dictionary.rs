@@ -0,0 +1,171 @@
+use atspi_common::Role;
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// Where a pronunciation rule applies.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Scope {
+	/// Everywhere.
+	Global,
+	/// Only while an application with this name is active.
+	Application(String),
+	/// Only while an item with this role (e.g. "push button") is focused.
+	Role(String),
+}
+
+impl Scope {
+	fn parse(scope: &str) -> Option<Self> {
+		match scope.split_once(':') {
+			None if scope == "global" => Some(Self::Global),
+			Some(("app", application)) => {
+				Some(Self::Application(application.to_string()))
+			}
+			Some(("role", role)) => Some(Self::Role(role.to_string())),
+			_ => None,
+		}
+	}
+	fn applies(&self, application: Option<&str>, role: Option<Role>) -> bool {
+		match self {
+			Self::Global => true,
+			Self::Application(name) => application == Some(name.as_str()),
+			Self::Role(name) => role.map_or(false, |role| role.name() == name),
+		}
+	}
+}
+
+/// A single substitution: text matching `pattern` is replaced with `replacement`.
+#[derive(Debug)]
+pub struct Rule {
+	pattern: Regex,
+	replacement: String,
+	/// Whether the replacement may refer to groups captured by the pattern, as in `$1`.
+	expand: bool,
+	scope: Scope,
+}
+
+impl Rule {
+	/// Parse a line of a dictionary: the pattern, its replacement, options and scope, separated by tabs.
+	/// Options are a comma separated list of `regex` (the pattern is a regular expression rather than literal text), `case` (matching is case sensitive) and `word` (only whole words match), or `-` for none of them.
+	/// The options and scope may be left out, in which case the rule is a literal, case insensitive, global one.
+	pub fn parse(line: &str) -> Result<Self, String> {
+		let mut fields = line.split('\t');
+		let (Some(pattern), Some(replacement)) = (fields.next(), fields.next()) else {
+			return Err("expected a pattern and a replacement".to_string());
+		};
+		let options = fields.next().unwrap_or("-");
+		let scope = fields.next().unwrap_or("global");
+		let scope = Scope::parse(scope).ok_or_else(|| format!("invalid scope: {scope}"))?;
+		let (mut regex, mut case, mut word) = (false, false, false);
+		for option in options.split(',').map(str::trim).filter(|option| *option != "-") {
+			match option {
+				"regex" => regex = true,
+				"case" => case = true,
+				"word" => word = true,
+				other => return Err(format!("invalid option: {other}")),
+			}
+		}
+		let pattern = if regex { pattern.to_string() } else { regex::escape(pattern) };
+		let pattern = if word { format!(r"\b(?:{pattern})\b") } else { pattern };
+		let pattern = RegexBuilder::new(&pattern)
+			.case_insensitive(!case)
+			.build()
+			.map_err(|e| e.to_string())?;
+		Ok(Self { pattern, replacement: replacement.to_string(), expand: regex, scope })
+	}
+	fn apply(&self, text: &str) -> String {
+		if self.expand {
+			self.pattern.replace_all(text, self.replacement.as_str()).into_owned()
+		} else {
+			self.pattern
+				.replace_all(text, NoExpand(&self.replacement))
+				.into_owned()
+		}
+	}
+}
+
+/// The user's pronunciation rules, applied in the order they are written.
+#[derive(Debug, Default)]
+pub struct PronunciationDictionary {
+	rules: Vec<Rule>,
+}
+
+impl PronunciationDictionary {
+	/// Parse a dictionary file. Empty lines and lines starting with `#` are skipped, as are invalid rules.
+	pub fn parse(contents: &str) -> Self {
+		let rules = contents
+			.lines()
+			.filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+			.filter_map(|line| match Rule::parse(line) {
+				Ok(rule) => Some(rule),
+				Err(e) => {
+					tracing::warn!(
+						line,
+						error = e,
+						"Invalid pronunciation rule"
+					);
+					None
+				}
+			})
+			.collect();
+		Self { rules }
+	}
+	/// Add the rules of `other` to this dictionary, to be applied after those already here.
+	pub fn append(&mut self, other: Self) {
+		self.rules.extend(other.rules);
+	}
+	pub fn len(&self) -> usize {
+		self.rules.len()
+	}
+	/// Apply every rule whose scope matches the current application and the role of the focused item.
+	pub fn apply(&self, text: &str, application: Option<&str>, role: Option<Role>) -> String {
+		self.rules
+			.iter()
+			.filter(|rule| rule.scope.applies(application, role))
+			.fold(text.to_string(), |text, rule| rule.apply(&text))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{PronunciationDictionary, Rule};
+	use atspi_common::Role;
+
+	#[test]
+	fn literal_rules() {
+		let dictionary = PronunciationDictionary::parse("# comment\nC++\tsee plus plus\n");
+		assert_eq!(dictionary.len(), 1);
+		assert_eq!(dictionary.apply("I like c++.", None, None), "I like see plus plus.");
+		let dictionary = PronunciationDictionary::parse("$1\tone dollar\tcase\n");
+		assert_eq!(dictionary.apply("costs $1", None, None), "costs one dollar");
+	}
+	#[test]
+	fn case_and_whole_words() {
+		let dictionary = PronunciationDictionary::parse("SQL\tsequel\tcase,word\n");
+		assert_eq!(
+			dictionary.apply("SQL, sql and MySQL", None, None),
+			"sequel, sql and MySQL"
+		);
+	}
+	#[test]
+	fn regex_rules() {
+		let dictionary = PronunciationDictionary::parse(r"(\d+)px	$1 pixels	regex");
+		assert_eq!(dictionary.apply("width: 20px", None, None), "width: 20 pixels");
+	}
+	#[test]
+	fn scoped_rules() {
+		let dictionary = PronunciationDictionary::parse(
+			"vim\tvee eye em\t-\tapp:terminal\nOK\tokay\tword\trole:push button\n",
+		);
+		assert_eq!(dictionary.apply("vim", Some("firefox"), None), "vim");
+		assert_eq!(dictionary.apply("vim", Some("terminal"), None), "vee eye em");
+		assert_eq!(dictionary.apply("OK", None, Some(Role::PushButton)), "okay");
+		assert_eq!(dictionary.apply("OK", None, Some(Role::Label)), "OK");
+	}
+	#[test]
+	fn invalid_rules() {
+		assert!(Rule::parse("no replacement").is_err());
+		assert!(Rule::parse("a\tb\tbold").is_err());
+		assert!(Rule::parse("a\tb\t-\tsomewhere").is_err());
+		assert!(Rule::parse("(\tb\tregex").is_err());
+		assert_eq!(PronunciationDictionary::parse("(\tb\tregex\na\tb\n").len(), 1);
+	}
+}
@@ -0,0 +1,98 @@
+//! Speaking embedded objects: applications put an object replacement character (U+FFFC) in the text of an item wherever one of its children, such as a link or an image, sits within that text.
+
+use odilia_cache::CacheItem;
+
+/// The character AT-SPI uses in place of an embedded object (like a link) within a block of text.
+pub const EMBEDDED_OBJECT: char = '\u{fffc}';
+
+/// How an embedded object is spoken: its role, then its text, as in "link, Home".
+pub fn render(role: &str, text: &str) -> String {
+	let text = text.replace(EMBEDDED_OBJECT, "");
+	let text = text.trim();
+	if text.is_empty() {
+		role.to_string()
+	} else {
+		format!("{role}, {text}")
+	}
+}
+
+/// Replace the embedded objects in `text`, which starts at the character offset `start` within its item, with their renderings.
+/// `objects` holds the rendering of each embedded object by its character offset; objects without one are left out.
+pub fn expand(text: &str, start: i32, objects: &[(i32, String)]) -> String {
+	let mut expanded = String::with_capacity(text.len());
+	let mut chars = (start..).zip(text.chars()).peekable();
+	while let Some((offset, c)) = chars.next() {
+		if c != EMBEDDED_OBJECT {
+			expanded.push(c);
+			continue;
+		}
+		let Some((_, rendering)) = objects.iter().find(|(at, _)| *at == offset) else {
+			continue;
+		};
+		// keep the rendering apart from the words around it
+		if expanded.chars().last().map_or(false, char::is_alphanumeric) {
+			expanded.push(' ');
+		}
+		expanded.push_str(rendering);
+		if chars.peek().map_or(false, |(_, c)| c.is_alphanumeric()) {
+			expanded.push(' ');
+		}
+	}
+	expanded
+}
+
+/// Replace the embedded objects in `text`, part of the text of `item` starting at the character offset `start`, with a rendering of the cached objects.
+/// Objects are found through the cached hyperlink offsets of the item; objects which can not be found are left out.
+pub async fn expand_item(item: &CacheItem, text: &str, start: i32) -> String {
+	if !text.contains(EMBEDDED_OBJECT) {
+		return text.to_string();
+	}
+	let Some(cache) = item.cache.upgrade() else {
+		return text.replace(EMBEDDED_OBJECT, "");
+	};
+	let links = match cache.get_or_create_hyperlinks(&item.object).await {
+		Ok(links) => links,
+		Err(e) => {
+			tracing::debug!(error = %e, "Could not get the embedded objects of an item");
+			Vec::new()
+		}
+	};
+	let objects: Vec<(i32, String)> = links
+		.into_iter()
+		.filter_map(|(offset, object)| {
+			let object = cache.get(&object)?;
+			Some((offset, render(object.role.name(), &object.text)))
+		})
+		.collect();
+	expand(text, start, &objects)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{expand, render};
+
+	#[test]
+	fn rendering() {
+		assert_eq!(render("link", "Home"), "link, Home");
+		assert_eq!(render("image", " "), "image");
+		assert_eq!(render("link", "\u{fffc} Home"), "link, Home");
+	}
+	#[test]
+	fn expanding() {
+		let objects = [(6, "link, Home".to_string()), (11, "link, About".to_string())];
+		assert_eq!(
+			expand("Go to \u{fffc} or \u{fffc}.", 0, &objects),
+			"Go to link, Home or link, About."
+		);
+		// part of the text, starting at the first object
+		assert_eq!(
+			expand("\u{fffc} or \u{fffc}", 6, &objects),
+			"link, Home or link, About"
+		);
+	}
+	#[test]
+	fn unknown_objects_are_left_out() {
+		assert_eq!(expand("a \u{fffc} b", 0, &[]), "a  b");
+		assert_eq!(expand("no objects", 3, &[(4, "link".to_string())]), "no objects");
+	}
+}
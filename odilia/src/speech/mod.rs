@@ -0,0 +1,137 @@
+//! Processing of text on its way to the speech synthesizer.
+
+pub mod capitals;
+pub mod dictionary;
+pub mod embedded;
+pub mod phonetic;
+pub mod punctuation;
+pub mod template;
+pub mod verbosity;
+
+use crate::state::ScreenReaderState;
+use dictionary::PronunciationDictionary;
+use odilia_common::{errors::OdiliaError, result::OdiliaResult, settings::PunctuationLevel};
+use ssip_client_async::{tokio::Request as SSIPRequest, ClientScope, Priority};
+use std::{fs, path::PathBuf};
+
+/// The user's main pronunciation dictionary, in the odilia configuration directory.
+const PRONUNCIATION_FILE: &str = "pronunciation.dic";
+/// A directory of further pronunciation dictionaries (any file ending in `.dic`), in the odilia configuration directory.
+const PRONUNCIATION_DIR: &str = "pronunciation";
+
+/// Prepare `text` to be spoken, according to the settings for the current application: first the user's pronunciation rules are applied, then the punctuation level.
+pub async fn prepare(state: &ScreenReaderState, text: &str) -> String {
+	let application = state.current_application.lock().await.clone();
+	let role = match state.history_item(0).await {
+		Some(focused) => state.cache.get(&focused).map(|item| item.role),
+		None => None,
+	};
+	let text = state
+		.pronunciation
+		.lock()
+		.await
+		.apply(text, application.as_deref(), role);
+	let level = match application.and_then(|application| {
+		state.config.punctuation().applications.get(&application).copied()
+	}) {
+		Some(level) => level,
+		None => *state.punctuation.lock().await,
+	};
+	state.symbols.apply(&text, level)
+}
+
+/// Say `text` at a different pitch, from -100 to 100, going back to the default pitch afterwards.
+/// Like [`ScreenReaderState::say`], this returns whether the text could be sent to the synthesizer.
+pub async fn say_at_pitch(
+	state: &ScreenReaderState,
+	priority: Priority,
+	text: String,
+	pitch: i8,
+) -> bool {
+	if state.ssip
+		.send(SSIPRequest::SetPitch(ClientScope::Current, pitch))
+		.await
+		.is_err()
+	{
+		return false;
+	}
+	let said = state.say(priority, text).await;
+	// odilia does not change the pitch otherwise, so it goes back to the default
+	state.ssip
+		.send(SSIPRequest::SetPitch(ClientScope::Current, 0))
+		.await
+		.is_ok() && said
+}
+
+/// Change the punctuation level for all applications which do not have a level of their own.
+pub async fn set_punctuation(state: &ScreenReaderState, level: PunctuationLevel) {
+	*state.punctuation.lock().await = level;
+	let name = serde_plain::to_string(&level).unwrap_or_default();
+	state.say(Priority::Text, format!("punctuation {name}")).await;
+}
+
+/// The order pronunciation dictionaries are loaded in: the main dictionary, if there is one, then those in the dictionary directory by file name.
+/// Files in the directory which do not end in `.dic` are left out.
+pub fn dictionary_order(main: Option<PathBuf>, mut others: Vec<PathBuf>) -> Vec<PathBuf> {
+	others.retain(|path| path.extension().map_or(false, |extension| extension == "dic"));
+	others.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+	main.into_iter().chain(others).collect()
+}
+
+/// Read the user's pronunciation dictionaries, if they have any: `pronunciation.dic`, then every `.dic` file in the `pronunciation` directory, in order of their file names.
+/// Rules from all of them are applied, those loaded first going first.
+/// # Errors
+/// Fails if the configuration directory can not be found, or a dictionary can not be read.
+pub fn load_pronunciation() -> OdiliaResult<PronunciationDictionary> {
+	let xdg_dirs = xdg::BaseDirectories::with_prefix("odilia")
+		.map_err(|e| OdiliaError::Generic(e.to_string()))?;
+	let mut dictionary = PronunciationDictionary::default();
+	for path in dictionary_order(
+		xdg_dirs.find_config_file(PRONUNCIATION_FILE),
+		xdg_dirs.list_config_files_once(PRONUNCIATION_DIR),
+	) {
+		let contents = fs::read_to_string(path)
+			.map_err(|e| OdiliaError::Generic(e.to_string()))?;
+		dictionary.append(PronunciationDictionary::parse(&contents));
+	}
+	Ok(dictionary)
+}
+
+/// Read the user's pronunciation dictionaries again, so that changes to them take effect without restarting.
+/// # Errors
+/// Fails if a dictionary can not be read; the rules already loaded are kept in that case.
+pub async fn reload_pronunciation(state: &ScreenReaderState) -> OdiliaResult<()> {
+	let dictionary = tokio::task::spawn_blocking(load_pronunciation)
+		.await
+		.map_err(|e| OdiliaError::Generic(e.to_string()))??;
+	let rules = dictionary.len();
+	*state.pronunciation.lock().await = dictionary;
+	state.say(Priority::Text, format!("Pronunciation dictionary reloaded, {rules} rules"))
+		.await;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::dictionary_order;
+	use std::path::PathBuf;
+
+	#[test]
+	fn dictionaries_in_order() {
+		let main = PathBuf::from("/config/odilia/pronunciation.dic");
+		let others = vec![
+			PathBuf::from("/config/odilia/pronunciation/work.dic"),
+			PathBuf::from("/config/odilia/pronunciation/README"),
+			PathBuf::from("/etc/xdg/odilia/pronunciation/acronyms.dic"),
+		];
+		assert_eq!(
+			dictionary_order(Some(main.clone()), others.clone()),
+			[
+				main,
+				PathBuf::from("/etc/xdg/odilia/pronunciation/acronyms.dic"),
+				PathBuf::from("/config/odilia/pronunciation/work.dic"),
+			]
+		);
+		assert_eq!(dictionary_order(None, Vec::new()), Vec::<PathBuf>::new());
+	}
+}
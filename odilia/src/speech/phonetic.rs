@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+/// The phonetic alphabets shipped with odilia, by language.
+const ALPHABETS: [(&str, &str); 2] = [
+	("en", include_str!("../../phonetic/en.dic")),
+	("es", include_str!("../../phonetic/es.dic")),
+];
+
+/// The language used when there is no alphabet for the user's language.
+const DEFAULT_LANGUAGE: &str = "en";
+
+/// The language part of a locale, e.g. `es` for `es_AR.UTF-8`.
+pub fn language(locale: &str) -> &str {
+	locale.split(['_', '.', '@', '-']).next().unwrap_or_default()
+}
+
+/// Words which characters are read as phonetically, as in "bravo" for "b".
+#[derive(Debug, Default)]
+pub struct PhoneticAlphabet {
+	words: HashMap<char, String>,
+}
+
+impl PhoneticAlphabet {
+	/// The alphabet shipped with odilia for `locale`, falling back on English.
+	pub fn for_locale(locale: &str) -> Self {
+		let language = language(locale);
+		let contents = ALPHABETS
+			.iter()
+			.find(|(alphabet, _)| *alphabet == language)
+			.or_else(|| {
+				ALPHABETS.iter().find(|(alphabet, _)| *alphabet == DEFAULT_LANGUAGE)
+			})
+			.map_or("", |(_, contents)| contents);
+		let mut alphabet = Self::default();
+		alphabet.load(contents);
+		alphabet
+	}
+	/// Add the entries of an alphabet file to this alphabet, replacing any existing entries for the same characters.
+	/// Lines which can not be understood are skipped.
+	pub fn load(&mut self, contents: &str) {
+		for line in contents.lines() {
+			if line.trim().is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let Some((character, word)) = line.split_once('\t') else {
+				tracing::debug!(line, "Invalid line in phonetic alphabet");
+				continue;
+			};
+			let mut chars = character.chars();
+			let (Some(character), None) = (chars.next(), chars.next()) else {
+				tracing::debug!(line, "Invalid line in phonetic alphabet");
+				continue;
+			};
+			self.words.extend(character
+				.to_lowercase()
+				.map(|c| (c, word.trim().to_string())));
+		}
+	}
+	/// The word `c` is read as, whatever its case.
+	pub fn word(&self, c: char) -> Option<&str> {
+		let mut lowercase = c.to_lowercase();
+		match (lowercase.next(), lowercase.next()) {
+			(Some(lowercase), None) => self.words.get(&lowercase).map(String::as_str),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{language, PhoneticAlphabet};
+
+	#[test]
+	fn languages() {
+		assert_eq!(language("es_AR.UTF-8"), "es");
+		assert_eq!(language("en"), "en");
+		assert_eq!(language("C.UTF-8"), "C");
+	}
+	#[test]
+	fn shipped_alphabets() {
+		let english = PhoneticAlphabet::for_locale("en_GB.UTF-8");
+		assert_eq!(english.word('b'), Some("bravo"));
+		assert_eq!(english.word('B'), Some("bravo"));
+		assert_eq!(english.word('7'), Some("seven"));
+		assert_eq!(english.word('!'), None);
+		let spanish = PhoneticAlphabet::for_locale("es_ES.UTF-8");
+		assert_eq!(spanish.word('Ñ'), Some("Ñoño"));
+		// no alphabet for the language, so English is used
+		assert_eq!(PhoneticAlphabet::for_locale("C").word('a'), Some("alfa"));
+	}
+	#[test]
+	fn user_entries_replace_shipped_ones() {
+		let mut alphabet = PhoneticAlphabet::for_locale("en");
+		alphabet.load("# mine\nA\table\nnot a valid line\n");
+		assert_eq!(alphabet.word('a'), Some("able"));
+		assert_eq!(alphabet.word('b'), Some("bravo"));
+	}
+}
@@ -0,0 +1,121 @@
+use odilia_common::settings::PunctuationLevel;
+use std::collections::HashMap;
+
+/// The dictionary shipped with odilia.
+pub const DEFAULT_SYMBOLS: &str = include_str!("../../symbols.dic");
+
+/// Spoken names of symbols, along with the lowest punctuation level each one is spoken at.
+#[derive(Debug, Default)]
+pub struct SymbolDictionary {
+	symbols: HashMap<char, (String, PunctuationLevel)>,
+}
+
+impl SymbolDictionary {
+	/// Add the entries of a dictionary file to this dictionary, replacing any existing entries for the same symbols.
+	/// Lines which can not be understood are skipped.
+	pub fn load(&mut self, contents: &str) {
+		for line in contents.lines() {
+			if line.trim().is_empty()
+				|| (line.starts_with('#') && !line.starts_with("#\t"))
+			{
+				continue;
+			}
+			let mut fields = line.split('\t');
+			let (Some(symbol), Some(name), Some(level)) =
+				(fields.next(), fields.next(), fields.next())
+			else {
+				tracing::debug!(line, "Invalid line in symbol dictionary");
+				continue;
+			};
+			let mut chars = symbol.chars();
+			let (Some(symbol), None, Ok(level)) =
+				(chars.next(), chars.next(), level.trim().parse())
+			else {
+				tracing::debug!(line, "Invalid line in symbol dictionary");
+				continue;
+			};
+			self.symbols.insert(symbol, (name.to_string(), level));
+		}
+	}
+	/// Replace the symbols in `text` which are spoken at `level` with their names.
+	/// A symbol on its own is always spoken, whatever the level, since that is how single characters are read.
+	pub fn apply(&self, text: &str, level: PunctuationLevel) -> String {
+		let mut chars = text.chars();
+		if let (Some(symbol), None) = (chars.next(), chars.next()) {
+			if let Some((name, _)) = self.symbols.get(&symbol) {
+				return name.clone();
+			}
+		}
+		let mut spoken = String::with_capacity(text.len());
+		let mut space_needed = false;
+		for c in text.chars() {
+			match self.symbols.get(&c) {
+				Some((name, symbol_level)) if *symbol_level <= level => {
+					if spoken
+						.chars()
+						.last()
+						.map_or(false, |last| !last.is_whitespace())
+					{
+						spoken.push(' ');
+					}
+					spoken.push_str(name);
+					space_needed = true;
+				}
+				_ => {
+					if space_needed && !c.is_whitespace() {
+						spoken.push(' ');
+					}
+					spoken.push(c);
+					space_needed = false;
+				}
+			}
+		}
+		spoken
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{SymbolDictionary, DEFAULT_SYMBOLS};
+	use odilia_common::settings::PunctuationLevel;
+
+	fn dictionary() -> SymbolDictionary {
+		let mut dictionary = SymbolDictionary::default();
+		dictionary.load(DEFAULT_SYMBOLS);
+		dictionary
+	}
+
+	#[test]
+	fn levels() {
+		let text = "Hello, (world) & more.";
+		let dictionary = dictionary();
+		assert_eq!(dictionary.apply(text, PunctuationLevel::None), text);
+		assert_eq!(
+			dictionary.apply(text, PunctuationLevel::Some),
+			"Hello, (world) and more."
+		);
+		assert_eq!(
+			dictionary.apply(text, PunctuationLevel::Most),
+			"Hello, left paren world right paren and more."
+		);
+		assert_eq!(
+			dictionary.apply(text, PunctuationLevel::All),
+			"Hello comma left paren world right paren and more dot"
+		);
+	}
+	#[test]
+	fn single_symbols_are_always_spoken() {
+		assert_eq!(dictionary().apply(".", PunctuationLevel::None), "dot");
+		assert_eq!(dictionary().apply("a", PunctuationLevel::All), "a");
+	}
+	#[test]
+	fn user_entries_replace_defaults() {
+		let mut dictionary = dictionary();
+		dictionary.load(
+			"# my symbols\n#\thash\tnone\n&\tampersand\tall\nnot a symbol line\n",
+		);
+		assert_eq!(dictionary.apply("a # b", PunctuationLevel::None), "a hash b");
+		assert_eq!(dictionary.apply("a & b", PunctuationLevel::Most), "a & b");
+		assert_eq!(dictionary.apply("&", PunctuationLevel::None), "ampersand");
+	}
+}
@@ -0,0 +1,189 @@
+use crate::{
+	commands::where_am_i::spoken_states,
+	speech::{embedded, verbosity::hint},
+	state::ScreenReaderState,
+};
+use atspi_client::convertable::Convertable;
+use atspi_common::{Interface, Role, State, StateSet};
+use atspi_proxies::{accessible::Accessible, action::ActionProxy};
+use odilia_cache::CacheItem;
+use odilia_common::{result::OdiliaResult, settings::TemplateField};
+use std::collections::HashMap;
+
+/// Speak the fields of a template in order, skipping any which are empty.
+pub fn render(template: &[TemplateField], fields: &HashMap<TemplateField, String>) -> String {
+	template.iter()
+		.filter_map(|field| fields.get(field))
+		.map(|text| text.trim())
+		.filter(|text| !text.is_empty())
+		.collect::<Vec<&str>>()
+		.join(", ")
+}
+
+/// Whether the text of an item is its value: only single-line entries, since reading the whole of a multi-line text or document on focus would go on for too long.
+/// The text of password fields is never read.
+pub fn text_is_value(states: StateSet, role: Role) -> bool {
+	states.contains(State::Editable)
+		&& !states.contains(State::MultiLine)
+		&& role != Role::PasswordText
+}
+
+async fn value(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<String> {
+	if item.interfaces.contains(Interface::Value) {
+		let value = item
+			.object
+			.clone()
+			.into_accessible(state.connection())
+			.await?
+			.to_value()
+			.await?
+			.current_value()
+			.await?;
+		return Ok(format!("{value}"));
+	}
+	if text_is_value(item.states, item.role) {
+		return Ok(item.text.clone());
+	}
+	Ok(String::new())
+}
+
+async fn shortcut(state: &ScreenReaderState, item: &CacheItem) -> OdiliaResult<String> {
+	let attributes = Accessible::get_attributes(item).await.unwrap_or_default();
+	if let Some(shortcut) = attributes.get("keyshortcuts") {
+		return Ok(shortcut.clone());
+	}
+	if !item.interfaces.contains(Interface::Action) {
+		return Ok(String::new());
+	}
+	let action: ActionProxy = item
+		.object
+		.clone()
+		.into_accessible(state.connection())
+		.await?
+		.to_action()
+		.await?;
+	// toolkits give the mnemonic, the full path to it and the accelerator, separated by semicolons
+	let binding = action.get_key_binding(0).await?;
+	Ok(binding
+		.rsplit(';')
+		.find(|key| !key.is_empty())
+		.unwrap_or_default()
+		.to_string())
+}
+
+/// Describe `item` using the template for its role, leaving out whatever the verbosity level does not include.
+/// `position` is the position of the item within its group, which callers work out since it depends on what was focused before.
+/// Only the fields the template uses are fetched.
+/// # Errors
+/// Fails if any of the `DBus` calls needed for the fields in the template fail.
+pub async fn describe(
+	state: &ScreenReaderState,
+	item: &CacheItem,
+	position: String,
+) -> OdiliaResult<String> {
+	let profile = state.verbosity.lock().await.profile();
+	let template: Vec<TemplateField> = state
+		.config
+		.templates()
+		.template(item.role.name())
+		.iter()
+		.copied()
+		.filter(|field| match field {
+			TemplateField::Role => profile.roles,
+			TemplateField::States => profile.states,
+			TemplateField::Position => profile.positions,
+			TemplateField::Description => profile.descriptions,
+			_ => true,
+		})
+		.collect();
+	let mut fields = HashMap::new();
+	for field in &template {
+		let text = match field {
+			TemplateField::Name => {
+				let name = item.name().await?;
+				if name.trim().is_empty() {
+					embedded::expand_item(item, &item.text, 0).await
+				} else {
+					name
+				}
+			}
+			TemplateField::Role => item.get_localized_role_name().await?,
+			TemplateField::States => {
+				let mut states = spoken_states(item.states);
+				// tree items give their expanded state along with their position
+				if item.role == Role::TreeItem {
+					states.retain(|state| {
+						!["expanded", "collapsed"].contains(state)
+					});
+				}
+				states.join(", ")
+			}
+			TemplateField::Value => value(state, item).await?,
+			TemplateField::Position => position.clone(),
+			TemplateField::Description => item.description().await?,
+			TemplateField::Shortcut => shortcut(state, item).await?,
+		};
+		fields.insert(*field, text);
+	}
+	let text = render(&template, &fields);
+	Ok(match hint(item.role, item.states).filter(|_| profile.hints) {
+		Some(hint) => format!("{text}. {hint}"),
+		None => text,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{render, text_is_value};
+	use atspi_common::{Role, State, StateSet};
+	use odilia_common::settings::TemplateField;
+	use std::collections::HashMap;
+
+	#[test]
+	fn fields_in_template_order() {
+		let fields = HashMap::from([
+			(TemplateField::Name, "OK".to_string()),
+			(TemplateField::Role, "push button".to_string()),
+			(TemplateField::Description, "Saves the file".to_string()),
+		]);
+		assert_eq!(
+			render(&[TemplateField::Role, TemplateField::Name], &fields),
+			"push button, OK"
+		);
+		assert_eq!(
+			render(
+				&[
+					TemplateField::Name,
+					TemplateField::Role,
+					TemplateField::Description
+				],
+				&fields
+			),
+			"OK, push button, Saves the file"
+		);
+	}
+	#[test]
+	fn empty_fields_are_skipped() {
+		let fields = HashMap::from([
+			(TemplateField::Name, "Volume".to_string()),
+			(TemplateField::States, " ".to_string()),
+			(TemplateField::Value, "50".to_string()),
+		]);
+		let template = [
+			TemplateField::Name,
+			TemplateField::States,
+			TemplateField::Shortcut,
+			TemplateField::Value,
+		];
+		assert_eq!(render(&template, &fields), "Volume, 50");
+	}
+	#[test]
+	fn text_of_single_line_entries_only() {
+		let entry = StateSet::new(State::Editable | State::SingleLine);
+		let document = StateSet::new(State::Editable | State::MultiLine);
+		assert!(text_is_value(entry, Role::Entry));
+		assert!(!text_is_value(document, Role::Text));
+		assert!(!text_is_value(entry, Role::PasswordText));
+		assert!(!text_is_value(StateSet::new(State::Focusable), Role::Label));
+	}
+}
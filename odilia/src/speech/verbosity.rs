@@ -0,0 +1,126 @@
+use crate::state::ScreenReaderState;
+use atspi_common::{Role, State, StateSet};
+use odilia_common::{errors::OdiliaError, result::OdiliaResult, settings::VerbosityLevel};
+use ssip_client_async::Priority;
+use std::fs;
+
+/// A hint on how to use an item, for beginners.
+pub fn hint(role: Role, states: StateSet) -> Option<&'static str> {
+	Some(match role {
+		Role::PushButton | Role::ToggleButton => "press space to activate",
+		Role::CheckBox | Role::CheckMenuItem => "press space to toggle",
+		Role::RadioButton => "use the arrow keys to choose an option",
+		Role::Link => "press enter to follow the link",
+		Role::ComboBox => "press alt and down arrow to open",
+		Role::Entry | Role::PasswordText if states.contains(State::Editable) => {
+			"type to enter text"
+		}
+		Role::Slider | Role::SpinButton => "use the arrow keys to change the value",
+		Role::PageTab => "use the left and right arrow keys to switch tabs",
+		Role::TreeItem if states.contains(State::Expanded) => {
+			"press left arrow to collapse"
+		}
+		Role::TreeItem if states.contains(State::Expandable) => {
+			"press right arrow to expand"
+		}
+		Role::MenuItem => "press enter to activate",
+		_ => return None,
+	})
+}
+
+/// Set `key` to `value` in `section` of an INI file, keeping everything else (including comments) as it is.
+/// The section is added at the end if it does not exist, as is the key if the section has no such key.
+pub fn set_ini_value(contents: &str, section: &str, key: &str, value: &str) -> String {
+	let header = format!("[{section}]");
+	let entry = format!("{key}={value}");
+	let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+	let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+		if !contents.is_empty() {
+			lines.push(String::new());
+		}
+		lines.push(header);
+		lines.push(entry);
+		return lines.join("\n") + "\n";
+	};
+	let end = lines[start + 1..]
+		.iter()
+		.position(|line| line.trim_start().starts_with('['))
+		.map_or(lines.len(), |offset| start + 1 + offset);
+	let existing = lines[start + 1..end].iter().position(|line| {
+		line.split_once('=').map_or(false, |(name, _)| name.trim() == key)
+	});
+	if let Some(offset) = existing {
+		lines[start + 1 + offset] = entry;
+	} else {
+		// keep any blank lines separating this section from the next one after the new key
+		let last = lines[start..end]
+			.iter()
+			.rposition(|line| !line.trim().is_empty())
+			.map_or(start, |offset| start + offset);
+		lines.insert(last + 1, entry);
+	}
+	lines.join("\n") + "\n"
+}
+
+/// Change the verbosity level, and save it in the configuration file so that it is kept after a restart.
+/// # Errors
+/// Fails if the configuration file can not be read or written; the new level is still used until odilia exits.
+pub async fn set_level(state: &ScreenReaderState, level: VerbosityLevel) -> OdiliaResult<()> {
+	*state.verbosity.lock().await = level;
+	let name = serde_plain::to_string(&level)?;
+	state.say(Priority::Text, format!("verbosity {name}")).await;
+	let path = state.config_path.clone();
+	// reading and writing the file blocks, so it is kept off the event loop
+	tokio::task::spawn_blocking(move || save_level(&path, &name))
+		.await
+		.map_err(|e| OdiliaError::Generic(e.to_string()))?
+}
+
+/// Write the verbosity level named `name` to the configuration file at `path`.
+fn save_level(path: &str, name: &str) -> OdiliaResult<()> {
+	let contents = fs::read_to_string(path).map_err(|e| OdiliaError::Generic(e.to_string()))?;
+	fs::write(path, set_ini_value(&contents, "verbosity", "level", name))
+		.map_err(|e| OdiliaError::Generic(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{hint, set_ini_value};
+	use atspi_common::{Role, State, StateSet};
+
+	const CONFIG: &str = "[speech]\nrate=100\n\n[verbosity]\n# beginner, standard or expert\nlevel=standard\n\n[log]\nlevel=\"debug\"\n";
+
+	#[test]
+	fn replacing_a_value() {
+		assert_eq!(
+			set_ini_value(CONFIG, "verbosity", "level", "expert"),
+			CONFIG.replace("level=standard", "level=expert")
+		);
+	}
+	#[test]
+	fn adding_a_value() {
+		let config = "[verbosity]\n# nothing yet\n\n[log]\nlevel=\"debug\"\n";
+		assert_eq!(
+			set_ini_value(config, "verbosity", "level", "expert"),
+			"[verbosity]\n# nothing yet\nlevel=expert\n\n[log]\nlevel=\"debug\"\n"
+		);
+		assert_eq!(
+			set_ini_value("[speech]\nrate=100\n", "verbosity", "level", "expert"),
+			"[speech]\nrate=100\n\n[verbosity]\nlevel=expert\n"
+		);
+	}
+	#[test]
+	fn hints_depend_on_state() {
+		assert_eq!(
+			hint(Role::TreeItem, StateSet::new(State::Expandable)),
+			Some("press right arrow to expand")
+		);
+		assert_eq!(
+			hint(Role::TreeItem, StateSet::new(State::Expandable | State::Expanded)),
+			Some("press left arrow to collapse")
+		);
+		assert_eq!(hint(Role::TreeItem, StateSet::empty()), None);
+		assert_eq!(hint(Role::Entry, StateSet::new(State::ReadOnly)), None);
+		assert_eq!(hint(Role::Label, StateSet::empty()), None);
+	}
+}
@@ -1,4 +1,4 @@
-use std::{fs, sync::atomic::AtomicI32};
+use std::{collections::HashMap, fs, sync::atomic::AtomicI32, time::Instant};
 
 use circular_queue::CircularQueue;
 use eyre::WrapErr;
@@ -7,34 +7,79 @@ use tokio::sync::{mpsc::Sender, Mutex};
 use tracing::debug;
 use zbus::{fdo::DBusProxy, names::UniqueName, zvariant::ObjectPath, MatchRule, MessageType};
 
-use atspi_client::{accessible_ext::AccessibleExt, convertable::Convertable};
 use atspi_common::{
 	events::{GenericEvent, HasMatchRule, HasRegistryEventString},
-	Event,
+	Event, State,
 };
 use atspi_connection::AccessibilityConnection;
 use atspi_proxies::{accessible::AccessibleProxy, cache::CacheProxy};
 use odilia_cache::{AccessiblePrimitive, Cache, CacheItem};
 use odilia_common::{
 	errors::{CacheError, ConfigError},
+	events::CaretKey,
 	modes::ScreenReaderMode,
-	settings::ApplicationConfig,
-	types::TextSelectionArea,
+	settings::{ApplicationConfig, PunctuationLevel, VerbosityLevel},
 	Result as OdiliaResult,
 };
 use std::sync::Arc;
 
+use crate::commands::{
+	alerts::AlertHistory, elements_list::ElementsList, indentation::Indentation,
+	progress::ProgressTracker, review::ReviewCursor,
+};
+use crate::speech::{
+	self,
+	dictionary::PronunciationDictionary,
+	phonetic::PhoneticAlphabet,
+	punctuation::{SymbolDictionary, DEFAULT_SYMBOLS},
+};
+
 #[allow(clippy::module_name_repetitions)]
 pub struct ScreenReaderState {
 	pub atspi: AccessibilityConnection,
 	pub dbus: DBusProxy<'static>,
 	pub ssip: Sender<SSIPRequest>,
 	pub config: ApplicationConfig,
+	/// Where the configuration was read from, so that settings changed at runtime can be saved.
+	pub config_path: String,
 	pub previous_caret_position: AtomicI32,
+	/// The level of the last focused tree item, or 0 if the last focused item was not in a tree.
+	pub previous_tree_level: AtomicI32,
 	pub mode: Mutex<ScreenReaderMode>,
 	pub accessible_history: Mutex<CircularQueue<AccessiblePrimitive>>,
 	pub event_history: Mutex<CircularQueue<Event>>,
 	pub cache: Arc<Cache>,
+	pub elements_list: Mutex<Option<ElementsList>>,
+	pub review_cursor: Mutex<Option<ReviewCursor>>,
+	pub navigator: Mutex<Option<AccessiblePrimitive>>,
+	pub last_where_am_i: Mutex<Option<Instant>>,
+	pub last_state_change: Mutex<Option<(AccessiblePrimitive, State, bool, Instant)>>,
+	pub current_window: Mutex<Option<AccessiblePrimitive>>,
+	/// Live region announcements held back until the region is no longer busy, by region.
+	pub live_pending: Mutex<HashMap<AccessiblePrimitive, Vec<(Priority, String)>>>,
+	pub alerts: Mutex<AlertHistory>,
+	pub progress: Mutex<ProgressTracker>,
+	/// The text selections of each object, as of the last selection change event for it.
+	pub selections: Mutex<HashMap<AccessiblePrimitive, Vec<(i32, i32)>>>,
+	/// The text attributes at the caret, as of the last time changes to them were announced.
+	pub caret_attributes: Mutex<HashMap<String, String>>,
+	/// The indentation of the line the caret was last on, for announcing changes to it.
+	pub last_indentation: Mutex<Option<Indentation>>,
+	/// The item last edited by typing, and where the caret is after the edit, so that the caret movement to there is not read.
+	pub last_edit: Mutex<Option<(AccessiblePrimitive, i32)>>,
+	/// The caret key most recently passed through to the application, and when.
+	pub last_caret_key: Mutex<Option<(CaretKey, Instant)>>,
+	/// The name of the application the current window belongs to.
+	pub current_application: Mutex<Option<String>>,
+	/// The application of the last activated window.
+	/// Unlike `current_window`, this is kept when the window is deactivated, so that switching between windows of the same application can be told apart from switching applications.
+	pub last_application: Mutex<Option<AccessiblePrimitive>>,
+	/// The punctuation level for applications without a level of their own in the configuration.
+	pub punctuation: Mutex<PunctuationLevel>,
+	pub symbols: SymbolDictionary,
+	pub phonetic: PhoneticAlphabet,
+	pub pronunciation: Mutex<PronunciationDictionary>,
+	pub verbosity: Mutex<VerbosityLevel>,
 }
 
 impl ScreenReaderState {
@@ -66,21 +111,82 @@ impl ScreenReaderState {
 			.wrap_err("unable to load configuration file")?;
 		tracing::debug!("configuration loaded successfully");
 
+		let mut symbols = SymbolDictionary::default();
+		symbols.load(DEFAULT_SYMBOLS);
+		if let Some(path) = xdg_dirs.find_config_file("symbols.dic") {
+			let user_symbols = fs::read_to_string(path)
+				.wrap_err("unable to read the symbol dictionary")?;
+			symbols.load(&user_symbols);
+		}
+		let locale = std::env::var("LANG").unwrap_or_default();
+		let mut phonetic = PhoneticAlphabet::for_locale(&locale);
+		if let Some(path) = xdg_dirs.find_config_file("phonetic.dic") {
+			let user_phonetic = fs::read_to_string(path)
+				.wrap_err("unable to read the phonetic alphabet")?;
+			phonetic.load(&user_phonetic);
+		}
+		let pronunciation = Mutex::new(
+			speech::load_pronunciation()
+				.wrap_err("unable to load the pronunciation dictionary")?,
+		);
+
 		let previous_caret_position = AtomicI32::new(0);
+		let previous_tree_level = AtomicI32::new(0);
 		let accessible_history = Mutex::new(CircularQueue::with_capacity(16));
 		let event_history = Mutex::new(CircularQueue::with_capacity(16));
 		let cache = Arc::new(Cache::new(atspi.connection().clone()));
+		let elements_list = Mutex::new(None);
+		let review_cursor = Mutex::new(None);
+		let navigator = Mutex::new(None);
+		let last_where_am_i = Mutex::new(None);
+		let last_state_change = Mutex::new(None);
+		let current_window = Mutex::new(None);
+		let live_pending = Mutex::new(HashMap::new());
+		let alerts = Mutex::new(AlertHistory::new(config.alerts().history_length));
+		let progress = Mutex::new(ProgressTracker::default());
+		let selections = Mutex::new(HashMap::new());
+		let caret_attributes = Mutex::new(HashMap::new());
+		let last_indentation = Mutex::new(None);
+		let last_edit = Mutex::new(None);
+		let last_caret_key = Mutex::new(None);
+		let current_application = Mutex::new(None);
+		let last_application = Mutex::new(None);
+		let punctuation = Mutex::new(config.punctuation().level);
+		let verbosity = Mutex::new(config.verbosity().level);
 
 		Ok(Self {
 			atspi,
 			dbus,
 			ssip,
 			config,
+			config_path,
 			previous_caret_position,
+			previous_tree_level,
 			mode,
 			accessible_history,
 			event_history,
 			cache,
+			elements_list,
+			review_cursor,
+			navigator,
+			last_where_am_i,
+			last_state_change,
+			current_window,
+			live_pending,
+			alerts,
+			progress,
+			selections,
+			caret_attributes,
+			last_indentation,
+			last_edit,
+			last_caret_key,
+			current_application,
+			last_application,
+			punctuation,
+			symbols,
+			phonetic,
+			pronunciation,
+			verbosity,
 		})
 	}
 
@@ -115,56 +221,6 @@ impl ScreenReaderState {
 		self.cache.get(&prim).ok_or(CacheError::NoItem.into())
 	}
 
-	// TODO: use cache; this will uplift performance MASSIVELY, also TODO: use this function instad of manually generating speech every time.
-	#[allow(dead_code)]
-	pub async fn generate_speech_string(
-		&self,
-		acc: AccessibleProxy<'_>,
-		select: TextSelectionArea,
-	) -> OdiliaResult<String> {
-		let acc_text = acc.to_text().await?;
-		let _acc_hyper = acc.to_hyperlink().await?;
-		//let _full_text = acc_text.get_text_ext().await?;
-		let (mut text_selection, start, end) = match select {
-			TextSelectionArea::Granular(granular) => {
-				acc_text.get_string_at_offset(granular.index, granular.granularity)
-					.await?
-			}
-			TextSelectionArea::Index(indexed) => (
-				acc_text.get_text(indexed.start, indexed.end).await?,
-				indexed.start,
-				indexed.end,
-			),
-		};
-		// TODO: Use streaming filters, or create custom function
-		let children = acc.get_children_ext().await?;
-		let mut children_in_range = Vec::new();
-		for child in children {
-			let child_hyper = child.to_hyperlink().await?;
-			let index = child_hyper.start_index().await?;
-			if index >= start && index <= end {
-				children_in_range.push(child);
-			}
-		}
-		for child in children_in_range {
-			let child_hyper = child.to_hyperlink().await?;
-			let child_start = usize::try_from(child_hyper.start_index().await?)?;
-			let child_end = usize::try_from(child_hyper.end_index().await?)?;
-			let child_text = format!(
-				"{}, {}",
-				child.name().await?,
-				child.get_role_name().await?
-			);
-			text_selection.replace_range(
-				child_start + (usize::try_from(start)?)
-					..child_end + (usize::try_from(start)?),
-				&child_text,
-			);
-		}
-		// TODO: add logic for punctuation
-		Ok(text_selection)
-	}
-
 	pub async fn register_event<E: HasRegistryEventString + HasMatchRule>(
 		&self,
 	) -> OdiliaResult<()> {
@@ -191,6 +247,7 @@ impl ScreenReaderState {
 	}
 
 	pub async fn say(&self, priority: Priority, text: String) -> bool {
+		let text = speech::prepare(self, &text).await;
 		if self.ssip.send(SSIPRequest::SetPriority(priority)).await.is_err() {
 			return false;
 		}
@@ -222,13 +279,21 @@ impl ScreenReaderState {
 		history.push(event);
 	}
 
+	/// The most recently activated window, if it is still active.
+	pub async fn current_window(&self) -> Option<CacheItem> {
+		let window = self.current_window.lock().await.clone()?;
+		self.cache.get(&window)
+	}
+
 	pub async fn history_item<'a>(&self, index: usize) -> Option<AccessiblePrimitive> {
 		let history = self.accessible_history.lock().await;
 		history.iter().nth(index).cloned()
 	}
 
 	/// Adds a new accessible to the history. We only store 16 previous accessibles, but theoretically, it should be lower.
+	/// The navigator object follows focus, so it is reset here as well.
 	pub async fn update_accessible(&self, new_a11y: AccessiblePrimitive) {
+		*self.navigator.lock().await = None;
 		let mut history = self.accessible_history.lock().await;
 		history.push(new_a11y);
 	}
@@ -240,7 +305,6 @@ impl ScreenReaderState {
 			.build()
 			.await?)
 	}
-	#[allow(dead_code)]
 	pub async fn get_or_create_cache_item(
 		&self,
 		accessible: AccessiblePrimitive,
@@ -254,19 +318,6 @@ impl ScreenReaderState {
 			.get_or_create(&accessible_proxy, Arc::downgrade(&self.cache))
 			.await
 	}
-	pub async fn new_accessible<'a, T: GenericEvent<'a>>(
-		&self,
-		event: &T,
-	) -> OdiliaResult<AccessibleProxy<'_>> {
-		let sender = event.sender().to_owned();
-		let path = event.path().to_owned();
-		Ok(AccessibleProxy::builder(self.connection())
-			.cache_properties(zbus::CacheProperties::No)
-			.destination(sender)?
-			.path(path)?
-			.build()
-			.await?)
-	}
 	pub async fn add_cache_match_rule(&self) -> OdiliaResult<()> {
 		let cache_rule = MatchRule::builder()
 			.msg_type(MessageType::Signal)
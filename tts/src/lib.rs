@@ -10,7 +10,7 @@
 use ssip_client_async::{
 	fifo::asynchronous_tokio::Builder,
 	tokio::{AsyncClient, Request},
-	ClientName,
+	ClientName, ClientScope, PunctuationMode, OK_PUNCTUATION_SET,
 };
 use std::{
 	io::ErrorKind,
@@ -60,6 +60,12 @@ pub async fn create_ssip_client(
 		.check_client_name_set()
 		.await?;
 	tracing::debug!("SSIP client registered as odilia:speech");
+	// odilia speaks punctuation itself, according to its own punctuation levels
+	ssip_core
+		.set_punctuation_mode(ClientScope::Current, PunctuationMode::None)
+		.await?
+		.check_status(OK_PUNCTUATION_SET)
+		.await?;
 	Ok(ssip_core)
 }
 